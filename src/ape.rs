@@ -1,5 +1,37 @@
 use crate::prelude::*;
 
+//////////////////////////////////////// Plugin ////////////////////////////////////////
+
+pub struct ApePlugin;
+
+impl Plugin for ApePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                make_ape,
+                move_apes,
+                trigger_ape_attack,
+                interrupt_apes.after(unit_attacks_ape),
+                recover_stunned_apes,
+                enrage_apes,
+                animate_apes_entrances,
+                animate_landing_dust,
+                update_apes_health_bars,
+                animate_apes_health_trails,
+                ape_attacks_player_collision,
+                animate_apes_wounds.before(advance_wave),
+                animate_apes_deaths,
+                animate_dead_icons,
+                animate_apes_attacks,
+                display_dead_apes_hud,
+                retune_apes,
+            )
+                .run_if(in_state(AppState::InGame)),
+        );
+    }
+}
+
 /////////////////////////////////////// Spawners ///////////////////////////////////////
 
 pub fn init_ape_icon(commands: &mut Commands, asset_server: &AssetServer) -> ApeIconHandle {
@@ -12,62 +44,268 @@ pub fn spawn_ape(
     commands: &mut Commands,
     asset_server: &AssetServer,
     texture_atlases: &mut Assets<TextureAtlas>,
-    flank: Flank,
+    font_handle: &Handle<Font>,
+    ape_icon: &ApeIconHandle,
+    rng: &mut GameRng,
+    tuning: &ApeTuning,
+    wave: &Wave,
+    wave_tuning: &WaveTuning,
+    slot: usize,
 ) {
-    let ape_name = ["ape_king", "ape_yacht", "ape_terminator"]
-        .choose(&mut rand::thread_rng())
-        .unwrap();
+    let kind = *ApeKind::ALL.choose(rng).unwrap();
+    let traits = ApeTraits::roll(rng, &tuning.traits);
+    let stats = tuning.kind(kind);
+    let SpawnSlot {
+        start,
+        min,
+        max,
+        flank,
+        perch,
+    } = tuning.slots[slot];
 
-    let ape_wound_image = asset_server.load(format!("{ape_name}_wound.png"));
-    let ape_wound_atlas =
-        TextureAtlas::from_grid(ape_wound_image, Vec2::new(600., 600.), 3, 1, None, None);
+    let position = Vec3::new(start, perch.unwrap_or(0.), 5.);
+    let entering = ApeEntering::new(stats.entrance, position, flank, tuning.entrance_duration);
 
     let ape = commands
         .spawn(Ape)
         .insert(SpriteBundle {
-            texture: asset_server.load(format!("{ape_name}.png")),
+            texture: asset_server.load(format!("{}.png", kind.name())),
             transform: Transform {
                 scale: Vec3::splat(0.8),
-                translation: Vec3::new(flank.start_pos(), 0., 5.),
+                translation: entering.from,
                 ..default()
             },
             sprite: Sprite {
                 flip_x: flank.flip_x(),
+                color: entering.color(traits.fur.color()),
                 ..default()
             },
             ..default()
         })
-        .insert(ApeWoundHandle(texture_atlases.add(ape_wound_atlas)))
+        .insert(ApeWoundHandle::load(asset_server, texture_atlases, kind))
         .insert(ApeWoundWidth(170. * 0.8))
-        .insert(ApeLife::new(1000.))
+        .insert(ApeLife::new(
+            stats.life * wave.life_scale(wave_tuning) * traits.life_scale(),
+        ))
+        .insert(kind)
+        .insert(traits)
         .insert(flank.initial_move())
+        .insert(ApeFeint::new(&tuning.steering))
+        .insert(ApeInterrupt::default())
+        .insert(ApeMinions::new(&tuning.minions))
+        .insert(ApeEnrage::new(stats.enrage_below))
+        .insert(ApeSlot {
+            index: slot,
+            min,
+            max,
+            perch,
+        })
         .insert(flank)
+        .insert(entering)
         .id();
 
-    let attack_range = ApeAttackRange::new(350., 155.)
-        .scaled_by(0.8)
-        .with_offset(PROJECTION_SCALE / 2.);
+    if perch.is_some() {
+        spawn_ape_ledge(commands, ape);
+    }
+    spawn_ape_traits_layers(commands, ape, &traits, flank);
+
+    let nameplate = format!("{} - Wave {}", traits.title(kind), wave.number);
+    let health_bar = spawn_ape_health_bar(
+        commands,
+        font_handle,
+        ape_icon,
+        nameplate,
+        traits.rarity().color(),
+    );
+    commands.entity(ape).insert(health_bar);
+    commands.entity(ape).push_children(&[health_bar.root]);
+
+    let ape_attack_spec = ApeAttackSpec::lasers(
+        asset_server,
+        texture_atlases,
+        kind,
+        stats.laser_reach,
+        stats.laser_charge * traits.laser_charge_scale(),
+        stats.laser_fire,
+        flank,
+    );
 
-    let laser_init_image = asset_server.load(format!("{ape_name}_blinking_eyes.png"));
-    let laser_init_atlas =
-        TextureAtlas::from_grid(laser_init_image, Vec2::new(900.0, 600.0), 2, 1, None, None);
+    let attack_interval = wave.attack_interval(wave_tuning, tuning.attack_interval);
+    let attack_schedule = ApeAttackSchedule::new(rng, attack_interval, tuning);
 
-    let laser_on_image = asset_server.load(format!("{ape_name}_lasers.png"));
-    let laser_on_atlas =
-        TextureAtlas::from_grid(laser_on_image, Vec2::new(900.0, 600.0), 3, 1, None, None);
+    commands
+        .entity(ape)
+        .insert(ape_attack_spec)
+        .insert(attack_schedule);
+}
 
-    let ape_attack_spec = ApeAttackSpec {
-        attack_range,
-        init_h: texture_atlases.add(laser_init_atlas),
-        init_duration: DurationTimer::from_seconds(0.6),
-        init_timer: Timer::from_seconds(0.1, TimerMode::Repeating),
-        on_h: texture_atlases.add(laser_on_atlas),
-        on_duration: DurationTimer::from_seconds(1.0),
-        on_timer: Timer::from_seconds(0.1, TimerMode::Repeating),
-        flank,
+/// Dead ape icons flying away from a dying ape.
+pub fn spawn_dead_icons_burst(commands: &mut Commands, asset_server: &AssetServer, position: Vec2) {
+    let count = 8;
+    for i in 0..count {
+        let angle = i as f32 * std::f32::consts::TAU / count as f32;
+        commands.spawn((
+            SpriteBundle {
+                texture: asset_server.load("ape_icon_dead.png"),
+                transform: Transform {
+                    translation: position.extend(30.),
+                    scale: Vec3::splat(0.08),
+                    ..default()
+                },
+                ..default()
+            },
+            DeadIcon {
+                velocity: Vec2::from_angle(angle) * 180.,
+                timer: Timer::from_seconds(ApeDeath::DURATION, TimerMode::Once),
+            },
+        ));
+    }
+}
+
+/// The health bar and nameplate floating above an ape, shown once it has entered.
+pub fn spawn_ape_health_bar(
+    commands: &mut Commands,
+    font_handle: &Handle<Font>,
+    ape_icon: &ApeIconHandle,
+    nameplate: String,
+    nameplate_color: Color,
+) -> ApeHealthBar {
+    let width = ApeHealthBar::WIDTH;
+    let segment = |commands: &mut Commands, color: Color, z: f32| {
+        let rect = shapes::Rectangle {
+            extents: Vec2::new(width, 10.),
+            origin: shapes::RectangleOrigin::CustomCenter(Vec2::new(width / 2., 0.)),
+        };
+        commands
+            .spawn((
+                ShapeBundle {
+                    path: GeometryBuilder::new().add(&rect).build(),
+                    spatial: SpatialBundle {
+                        transform: Transform::from_xyz(-width / 2., 0., z),
+                        ..default()
+                    },
+                    ..default()
+                },
+                Fill::color(color),
+            ))
+            .id()
+    };
+
+    let background = segment(commands, Color::rgba(0., 0., 0., 0.5), 0.);
+    let trail = segment(commands, Color::WHITE, 1.);
+    commands.entity(trail).insert(ApeHealthTrail::default());
+    let fill = segment(commands, Color::PINK, 2.);
+    commands.entity(fill).insert(ApeHealthFill);
+
+    // Marks how much life is left before the ape gets enraged
+    let mark = shapes::Rectangle {
+        extents: Vec2::new(3., 16.),
+        origin: shapes::RectangleOrigin::default(),
+    };
+    let mark = commands
+        .spawn((
+            ShapeBundle {
+                path: GeometryBuilder::new().add(&mark).build(),
+                spatial: SpatialBundle {
+                    transform: Transform::from_xyz(0., 0., 3.),
+                    ..default()
+                },
+                ..default()
+            },
+            Fill::color(Color::RED),
+            EnrageMark,
+        ))
+        .id();
+
+    let icon = commands
+        .spawn(SpriteBundle {
+            texture: ape_icon.0.clone(),
+            transform: Transform {
+                scale: Vec3::splat(0.4),
+                translation: Vec3::new(-width / 2. - 20., 0., 0.),
+                ..default()
+            },
+            ..default()
+        })
+        .id();
+
+    let name = commands
+        .spawn(Text2dBundle {
+            text: Text::from_section(
+                nameplate,
+                TextStyle {
+                    font: font_handle.clone(),
+                    font_size: 28.0,
+                    color: nameplate_color,
+                },
+            )
+            .with_alignment(TextAlignment::Center),
+            transform: Transform::from_xyz(0., 30., 0.),
+            ..default()
+        })
+        .id();
+
+    let root = commands
+        .spawn(SpatialBundle {
+            transform: Transform::from_xyz(10., 300., 15.),
+            visibility: Visibility::Hidden,
+            ..default()
+        })
+        .push_children(&[background, trail, fill, mark, icon, name])
+        .id();
+
+    ApeHealthBar {
+        root,
+        fill,
+        trail,
+        mark,
+    }
+}
+
+/// A dust cloud blown away from where a dropped ape lands.
+pub fn spawn_landing_dust(commands: &mut Commands, position: Vec2) {
+    let cloud = shapes::Ellipse {
+        radii: Vec2::new(60., 12.),
+        center: Vec2::ZERO,
     };
+    commands.spawn((
+        ShapeBundle {
+            path: GeometryBuilder::new().add(&cloud).build(),
+            spatial: SpatialBundle {
+                transform: Transform::from_translation(position.extend(6.)),
+                ..default()
+            },
+            ..default()
+        },
+        Fill::color(Color::rgba(0.8, 0.75, 0.65, 0.8)),
+        LandingDust(Timer::from_seconds(LandingDust::DURATION, TimerMode::Once)),
+    ));
+}
 
-    commands.entity(ape).insert(ape_attack_spec);
+/// The floating ledge a perched ape stands on, it moves along with the ape.
+pub fn spawn_ape_ledge(commands: &mut Commands, ape: Entity) {
+    let ledge = commands
+        .spawn((
+            ShapeBundle {
+                path: GeometryBuilder::new()
+                    .add(&shapes::Rectangle {
+                        extents: Vec2::new(300., 24.),
+                        origin: shapes::RectangleOrigin::Center,
+                    })
+                    .build(),
+                spatial: SpatialBundle {
+                    // Under the feet of the ape, in its scaled space
+                    transform: Transform::from_xyz(0., -312., -1.),
+                    ..default()
+                },
+                ..default()
+            },
+            Fill::color(Color::rgb(0.35, 0.25, 0.2)),
+            Stroke::new(Color::BLACK, 3.),
+        ))
+        .id();
+
+    commands.entity(ape).push_children(&[ledge]);
 }
 
 pub fn spawn_ape_attack_init(commands: &mut Commands, ape: Entity, attack_spec: &ApeAttackSpec) {
@@ -77,10 +315,7 @@ pub fn spawn_ape_attack_init(commands: &mut Commands, ape: Entity, attack_spec:
         ..
     } = attack_spec;
 
-    let offset_x = match attack_spec.flank {
-        Flank::Left => 150.,
-        Flank::Right => -150.,
-    };
+    let offset_x = attack_spec.flank.facing_sign() * ApeAttackSpec::LASERS_X;
 
     let animation = commands
         .spawn(SpriteSheetBundle {
@@ -96,6 +331,7 @@ pub fn spawn_ape_attack_init(commands: &mut Commands, ape: Entity, attack_spec:
             init_duration.clone(),
             init_timer.clone(),
         ))
+        .insert(ApeAttackOwner(ape))
         .id();
 
     commands.entity(ape).push_children(&[animation]);
@@ -106,18 +342,22 @@ pub fn spawn_ape_attack_on(commands: &mut Commands, ape: Entity, attack_spec: &A
         on_duration,
         on_timer,
         attack_range,
+        reach,
         ..
     } = attack_spec;
 
-    let offset_x = match attack_spec.flank {
-        Flank::Left => 150.,
-        Flank::Right => -150.,
-    };
+    // Stretches the lasers from the eyes
+    let offset_x =
+        attack_spec.flank.facing_sign() * (150. + (1. - reach) * ApeAttackRange::LASERS_EYES_X);
 
     let animation = commands
         .spawn(SpriteSheetBundle {
             texture_atlas: attack_spec.on_h.clone(),
-            transform: Transform::from_xyz(offset_x, 0., 10.),
+            transform: Transform {
+                translation: Vec3::new(offset_x, 0., 10.),
+                scale: Vec3::new(*reach, 1., 1.),
+                ..default()
+            },
             sprite: TextureAtlasSprite {
                 flip_x: attack_spec.flank.flip_x(),
                 ..default()
@@ -128,19 +368,20 @@ pub fn spawn_ape_attack_on(commands: &mut Commands, ape: Entity, attack_spec: &A
         .insert(StagedAnimation::on(on_duration.clone(), on_timer.clone()))
         .insert(*attack_range)
         .insert(attack_spec.flank)
+        .insert(ApeAttackOwner(ape))
         .id();
 
     commands.entity(ape).push_children(&[animation]);
 }
 
-pub fn spawn_ape_damaged_anim(
+/// The wound flash played over a wounded ape.
+pub fn spawn_ape_wound_anim(
     commands: &mut Commands,
     ape_life: &ApeLife,
     wound_h: &ApeWoundHandle,
-    ape_icon_h: &ApeIconHandle,
     flank: &Flank,
 ) -> Entity {
-    let anim = commands
+    commands
         .spawn(SpriteSheetBundle {
             texture_atlas: wound_h.0.clone(),
             transform: Transform::from_xyz(0., 0., 9.),
@@ -156,44 +397,7 @@ pub fn spawn_ape_damaged_anim(
         })
         .insert(wound_h.clone())
         .insert(*ape_life)
-        .id();
-
-    if ape_life.current > 0. {
-        let rect_x = ape_life.current * 200. / ape_life.max;
-        let rect = shapes::Rectangle {
-            extents: Vec2::new(rect_x, 10.),
-            origin: shapes::RectangleOrigin::default(),
-        };
-        let builder = GeometryBuilder::new().add(&rect);
-        let healthbar = commands
-            .spawn((
-                ShapeBundle {
-                    path: builder.build(),
-                    spatial: SpatialBundle {
-                        transform: Transform::from_xyz(10., 300., 15.),
-                        ..default()
-                    },
-                    ..default()
-                },
-                Fill::color(Color::PINK),
-            ))
-            .id();
-        commands.entity(anim).push_children(&[healthbar]);
-
-        let icon = commands
-            .spawn(SpriteBundle {
-                texture: ape_icon_h.0.clone(),
-                transform: Transform {
-                    scale: Vec3::splat(0.4),
-                    translation: Vec3::new(-rect_x / 2. - 20., 0., 0.),
-                    ..default()
-                },
-                ..default()
-            })
-            .id();
-        commands.entity(healthbar).push_children(&[icon]);
-    }
-    anim
+        .id()
 }
 
 pub fn spawn_dead_apes_hud(
@@ -239,18 +443,76 @@ pub fn spawn_dead_apes_hud(
 #[derive(Component)]
 pub struct Ape;
 
+impl Ape {
+    /// Closest two apes can get
+    pub const SPACING: f32 = 170. * 0.8;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Component)]
+pub enum ApeKind {
+    King,
+    Yacht,
+    Terminator,
+}
+
+impl ApeKind {
+    pub const ALL: [Self; 3] = [Self::King, Self::Yacht, Self::Terminator];
+
+    /// Prefix of the kind's images
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::King => "ape_king",
+            Self::Yacht => "ape_yacht",
+            Self::Terminator => "ape_terminator",
+        }
+    }
+
+    /// Name of the kind as shown to the player
+    pub fn title(&self) -> &'static str {
+        match self {
+            Self::King => "King",
+            Self::Yacht => "Yacht",
+            Self::Terminator => "Terminator",
+        }
+    }
+
+    /// Name of the kind's section in the tuning file
+    pub fn tuning_key(&self) -> &'static str {
+        match self {
+            Self::King => "king",
+            Self::Yacht => "yacht",
+            Self::Terminator => "terminator",
+        }
+    }
+}
+
 #[derive(Resource, Clone)]
 pub struct ApeIconHandle(pub Handle<Image>);
 
 #[derive(Clone, Component)]
 pub struct ApeWoundHandle(pub Handle<TextureAtlas>);
 
+impl ApeWoundHandle {
+    pub fn load(
+        asset_server: &AssetServer,
+        texture_atlases: &mut Assets<TextureAtlas>,
+        kind: ApeKind,
+    ) -> Self {
+        let image = asset_server.load(format!("{}_wound.png", kind.name()));
+        let atlas = TextureAtlas::from_grid(image, Vec2::new(600., 600.), 3, 1, None, None);
+        Self(texture_atlases.add(atlas))
+    }
+}
+
 #[derive(Component)]
 pub struct ApeWoundWidth(pub f32);
 
 #[derive(Component)]
 pub struct ApeAttackSpec {
+    pub kind: ApeKind,
     pub attack_range: ApeAttackRange,
+    /// Length of the lasers, relative to the sprite
+    pub reach: f32,
     pub init_h: Handle<TextureAtlas>,
     pub init_duration: DurationTimer,
     pub init_timer: Timer,
@@ -260,6 +522,45 @@ pub struct ApeAttackSpec {
     pub flank: Flank,
 }
 
+impl ApeAttackSpec {
+    /// Distance from the ape to the center of its lasers sprite
+    pub const LASERS_X: f32 = 150.;
+
+    /// The eyes lasers of `kind`, for an ape sprite scaled like regular apes.
+    pub fn lasers(
+        asset_server: &AssetServer,
+        texture_atlases: &mut Assets<TextureAtlas>,
+        kind: ApeKind,
+        reach: f32,
+        charge: f32,
+        fire: f32,
+        flank: Flank,
+    ) -> Self {
+        let ape_name = kind.name();
+
+        let laser_init_image = asset_server.load(format!("{ape_name}_blinking_eyes.png"));
+        let laser_init_atlas =
+            TextureAtlas::from_grid(laser_init_image, Vec2::new(900.0, 600.0), 2, 1, None, None);
+
+        let laser_on_image = asset_server.load(format!("{ape_name}_lasers.png"));
+        let laser_on_atlas =
+            TextureAtlas::from_grid(laser_on_image, Vec2::new(900.0, 600.0), 3, 1, None, None);
+
+        Self {
+            kind,
+            attack_range: ApeAttackRange::lasers(reach),
+            reach,
+            init_h: texture_atlases.add(laser_init_atlas),
+            init_duration: DurationTimer::from_seconds(charge),
+            init_timer: Timer::from_seconds(0.1, TimerMode::Repeating),
+            on_h: texture_atlases.add(laser_on_atlas),
+            on_duration: DurationTimer::from_seconds(fire),
+            on_timer: Timer::from_seconds(0.1, TimerMode::Repeating),
+            flank,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Component)]
 pub struct ApeAttackRange {
     offset_x: f32,
@@ -267,10 +568,22 @@ pub struct ApeAttackRange {
 }
 
 impl ApeAttackRange {
+    /// Distance from the center of the lasers sprite to the eyes of the ape
+    pub const LASERS_EYES_X: f32 = 162.5;
+    /// Height of the player standing on the floor
+    const PLAYER_FLOOR_Y: f32 = -170.;
+    /// Distance from the height of a perched ape within which its lasers hit the player
+    const PERCHED_HALF_HEIGHT: f32 = 60.;
+
     pub fn new(offset_x: f32, range_x: f32) -> Self {
         Self { offset_x, range_x }
     }
 
+    /// Whether something at `y` is at the height of an ape perched at `perch`.
+    pub fn level_with_perch(perch: f32, y: f32) -> bool {
+        (y - Self::PLAYER_FLOOR_Y - perch).abs() < Self::PERCHED_HALF_HEIGHT
+    }
+
     pub fn scaled_by(self, scale: f32) -> Self {
         Self {
             offset_x: self.offset_x * scale,
@@ -284,6 +597,19 @@ impl ApeAttackRange {
             range_x: self.range_x,
         }
     }
+
+    /// Distance from the lasers sprite to the middle of the range.
+    pub fn center(&self) -> f32 {
+        self.offset_x + self.range_x / 2.
+    }
+
+    /// Range of the eyes lasers, stretched by `reach` from the eyes.
+    pub fn lasers(reach: f32) -> Self {
+        Self::new(350., 155.)
+            .scaled_by(0.8)
+            .with_offset(PROJECTION_SCALE / 2.)
+            .scaled_by(reach)
+    }
 }
 
 #[derive(Clone, Copy, Component)]
@@ -303,6 +629,12 @@ impl ApeLife {
     pub fn decrease_by(&mut self, amount: f32) {
         self.current = (self.current - amount).max(0.);
     }
+
+    /// Changes the max life while keeping the same life ratio.
+    pub fn rescale(&mut self, max: f32) {
+        self.current = self.current / self.max * max;
+        self.max = max;
+    }
 }
 
 #[derive(Component)]
@@ -314,26 +646,63 @@ pub struct DeadApesText;
 #[derive(Component, Deref)]
 pub struct DeadApesCounter(usize);
 
-#[derive(Component, Deref)]
-pub struct ApesAliveAt(Instant);
+/// Delay before the next attack of an ape, each ape keeps its own rhythm.
+#[derive(Component, Deref, DerefMut)]
+pub struct ApeAttackSchedule(Timer);
+
+impl ApeAttackSchedule {
+    pub fn new(rng: &mut GameRng, interval: f32, tuning: &ApeTuning) -> Self {
+        Self(Timer::from_seconds(
+            Self::jittered(rng, interval, tuning),
+            TimerMode::Once,
+        ))
+    }
+
+    pub fn reschedule(&mut self, rng: &mut GameRng, interval: f32, tuning: &ApeTuning) {
+        let delay = Self::jittered(rng, interval, tuning);
+        self.set_duration(Duration::from_secs_f32(delay));
+        self.reset();
+    }
+
+    /// Randomly shortens or lengthens `interval`, but never below the cooldown.
+    fn jittered(rng: &mut GameRng, interval: f32, tuning: &ApeTuning) -> f32 {
+        let jitter = rng.gen_range(-tuning.attack_jitter..=tuning.attack_jitter);
+        (interval * (1. + jitter)).max(tuning.attack_cooldown)
+    }
+}
+
+/// The latest attack among all apes, for the coordination rules.
+#[derive(Default)]
+pub struct ApesAttackLog {
+    pub last: Option<Entity>,
+    pub since_last: Duration,
+}
+
+/// Delay before missing apes are respawned, restarted as long as the wave flanks are filled.
+///
+/// Its duration is set from the tuning before each tick.
+#[derive(Component, Deref, DerefMut)]
+pub struct ApesRespawn(Timer);
 
-impl Default for ApesAliveAt {
+impl Default for ApesRespawn {
     fn default() -> Self {
-        Self(Instant::now())
+        Self(Timer::new(Duration::ZERO, TimerMode::Once))
     }
 }
 
-#[derive(Copy, Clone, PartialEq, Eq, Hash, Component)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Component, Deserialize)]
 pub enum Flank {
     Left,
     Right,
 }
 
 impl Flank {
-    pub fn start_pos(&self) -> f32 {
-        match self {
-            Self::Left => self.min(),
-            Self::Right => self.max(),
+    /// The flank an ape at `x` fights from to face `target_x`.
+    pub fn facing(x: f32, target_x: f32) -> Self {
+        if target_x < x {
+            Self::Right
+        } else {
+            Self::Left
         }
     }
 
@@ -344,17 +713,11 @@ impl Flank {
         }
     }
 
-    pub fn min(&self) -> f32 {
-        match self {
-            Self::Left => -(GLOBAL_WIDTH / 2. - 210. * 0.8),
-            Self::Right => 145. * 0.8,
-        }
-    }
-
-    pub fn max(&self) -> f32 {
+    /// 1 when apes on this flank face right, -1 otherwise.
+    pub fn facing_sign(&self) -> f32 {
         match self {
-            Self::Left => -145. * 0.8,
-            Self::Right => GLOBAL_WIDTH / 2. - 210. * 0.8,
+            Self::Left => 1.,
+            Self::Right => -1.,
         }
     }
 
@@ -366,69 +729,697 @@ impl Flank {
     }
 }
 
+/// The spawn slot held by an ape, see [`SpawnSlot`].
+#[derive(Clone, Copy, Component)]
+pub struct ApeSlot {
+    pub index: usize,
+    /// Patrol bounds
+    pub min: f32,
+    pub max: f32,
+    /// Height of its ledge, on the floor otherwise
+    pub perch: Option<f32>,
+}
+
+/// Damages taken by an ape during the charge of its current attack.
+#[derive(Component, Default)]
+pub struct ApeInterrupt {
+    pub damages: f32,
+}
+
+/// An ape whose charge got interrupted, it can neither move nor attack for a while.
+#[derive(Component, Deref, DerefMut)]
+pub struct ApeStunned(pub Timer);
+
+impl ApeStunned {
+    pub const TINT: Color = Color::rgb(0.55, 0.55, 1.);
+}
+
+/// Below a share of its life, an ape moves faster, charges its lasers quicker
+/// and may chain its attacks.
+#[derive(Component)]
+pub struct ApeEnrage {
+    pub below: f32,
+    pub enraged: bool,
+}
+
+impl ApeEnrage {
+    pub const TINT: Color = Color::rgb(1., 0.55, 0.55);
+
+    pub fn new(below: f32) -> Self {
+        Self {
+            below,
+            enraged: false,
+        }
+    }
+
+    pub fn reached_by(&self, ape_life: &ApeLife) -> bool {
+        ape_life.current > 0. && ape_life.current < ape_life.max * self.below
+    }
+
+    /// The color of the ape sprite when nothing else tints it, `fur` being its own.
+    pub fn tint(&self, fur: Color) -> Color {
+        if self.enraged {
+            Self::TINT
+        } else {
+            fur
+        }
+    }
+}
+
+/// The attack an enraged ape starts as soon as its current one is over.
+#[derive(Component)]
+pub struct ApeChain(pub ApeAttackKind);
+
+/// The entities of the health bar of an ape, see [`spawn_ape_health_bar`].
+#[derive(Clone, Copy, Component)]
+pub struct ApeHealthBar {
+    pub root: Entity,
+    pub fill: Entity,
+    pub trail: Entity,
+    pub mark: Entity,
+}
+
+impl ApeHealthBar {
+    pub const WIDTH: f32 = 200.;
+}
+
+/// The part of the health bar showing the life left.
+#[derive(Component)]
+pub struct ApeHealthFill;
+
+/// The part of the health bar showing the recent damages, it catches up with the life
+/// left once the ape has not been hurt for a while.
+#[derive(Component)]
+pub struct ApeHealthTrail {
+    /// Shares of the life shown and left
+    pub shown: f32,
+    pub target: f32,
+    pub delay: Timer,
+}
+
+impl ApeHealthTrail {
+    const DELAY: f32 = 0.5;
+    /// Share of the life caught up per second
+    const SPEED: f32 = 0.5;
+}
+
+impl Default for ApeHealthTrail {
+    fn default() -> Self {
+        Self {
+            shown: 1.,
+            target: 1.,
+            delay: Timer::from_seconds(Self::DELAY, TimerMode::Once),
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct EnrageMark;
+
+/// An ape showing up in the arena, it can't be hurt nor attack until it is done.
+#[derive(Component)]
+pub struct ApeEntering {
+    pub entrance: ApeEntrance,
+    pub timer: Timer,
+    pub from: Vec3,
+    /// Where the ape starts fighting
+    pub to: Vec3,
+}
+
+impl ApeEntering {
+    /// How far above its spot a dropped ape starts, out of the screen
+    const DROP_HEIGHT: f32 = 600.;
+    /// Distance between the edge of the arena and where a sliding ape starts
+    const SLIDE_MARGIN: f32 = 200.;
+    /// Distance between the feet of an ape and its center
+    const FEET_Y: f32 = -240.;
+
+    pub fn new(entrance: ApeEntrance, to: Vec3, flank: Flank, duration: f32) -> Self {
+        let from = match entrance {
+            ApeEntrance::Drop => to + Vec3::Y * Self::DROP_HEIGHT,
+            ApeEntrance::Slide => Vec3::new(
+                -flank.facing_sign() * (GLOBAL_WIDTH / 2. + Self::SLIDE_MARGIN),
+                to.y,
+                to.z,
+            ),
+            ApeEntrance::Teleport => to,
+        };
+        Self {
+            entrance,
+            timer: Timer::from_seconds(duration, TimerMode::Once),
+            from,
+            to,
+        }
+    }
+
+    pub fn position(&self) -> Vec3 {
+        let t = self.timer.percent();
+        match self.entrance {
+            // Speeds up while falling
+            ApeEntrance::Drop => self.from.lerp(self.to, t * t),
+            // Slows down before stopping
+            ApeEntrance::Slide => self.from.lerp(self.to, 1. - (1. - t) * (1. - t)),
+            ApeEntrance::Teleport => self.to,
+        }
+    }
+
+    pub fn color(&self, fur: Color) -> Color {
+        match self.entrance {
+            ApeEntrance::Teleport => {
+                let shimmer = (self.timer.elapsed_secs() * 40.).sin().abs();
+                Color::rgba(0.6, 1., 1., self.timer.percent() * (0.5 + 0.5 * shimmer))
+            }
+            ApeEntrance::Drop | ApeEntrance::Slide => fur,
+        }
+    }
+}
+
+#[derive(Component, Deref, DerefMut)]
+pub struct LandingDust(Timer);
+
+impl LandingDust {
+    const DURATION: f32 = 0.5;
+}
+
+/// Plays once an ape is killed: it collapses, falls off its ledge if any, and fades away.
+#[derive(Component)]
+pub struct ApeDeath {
+    timer: Timer,
+    /// Height the ape collapses to
+    rest_y: f32,
+}
+
+impl ApeDeath {
+    pub const DURATION: f32 = 1.2;
+    /// How far a collapsed ape sinks below where it stood
+    const SINK: f32 = 60.;
+
+    pub fn new(floor_y: f32) -> Self {
+        Self {
+            timer: Timer::from_seconds(Self::DURATION, TimerMode::Once),
+            rest_y: floor_y - Self::SINK,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct DeadIcon {
+    velocity: Vec2,
+    timer: Timer,
+}
+
+/// Paces the rushes of apes steered by [`ApeSteering::Feint`].
+#[derive(Component, Deref, DerefMut)]
+pub struct ApeFeint(Timer);
+
+impl ApeFeint {
+    pub fn new(tuning: &ApeSteeringTuning) -> Self {
+        Self(Timer::from_seconds(
+            tuning.feint_interval,
+            TimerMode::Repeating,
+        ))
+    }
+
+    /// Rushes happen at the end of each interval.
+    pub fn rushing(&self, tuning: &ApeSteeringTuning) -> bool {
+        self.elapsed_secs() >= tuning.feint_interval - tuning.feint_duration
+    }
+}
+
 /////////////////////////////////////// Systems ////////////////////////////////////////
 
 pub fn make_ape(
+    time: Res<Time>,
+    tuning: Res<ApeTuning>,
+    wave: Res<Wave>,
+    wave_tuning: Res<WaveTuning>,
+    boss_encounter: Res<BossEncounter>,
     mut commands: Commands,
-    apes_q: Query<&Flank, With<Ape>>,
+    apes_q: Query<&ApeSlot, With<Ape>>,
     asset_server: Res<AssetServer>,
+    font_handle: Res<FontHandle>,
+    ape_icon: Res<ApeIconHandle>,
     mut texture_atlases: ResMut<Assets<TextureAtlas>>,
-    mut apes_alive_at: Local<ApesAliveAt>,
+    mut rng: ResMut<GameRng>,
+    mut apes_respawn: Local<ApesRespawn>,
 ) {
-    let apes_flanks = apes_q.iter().cloned().collect::<HashSet<_>>();
-
-    if apes_flanks.len() == 2 {
-        *apes_alive_at = ApesAliveAt::default();
+    let held_slots = apes_q.iter().map(|slot| slot.index).collect::<HashSet<_>>();
+    let missing_slots = wave
+        .slots(&wave_tuning, tuning.slots.len())
+        .filter(|slot| !held_slots.contains(slot))
+        .collect::<Vec<_>>();
+
+    if missing_slots.is_empty() {
+        apes_respawn.reset();
     }
-
-    if apes_alive_at.elapsed() > Duration::from_secs(3) {
-        for flank in [Flank::Left, Flank::Right] {
-            if !apes_flanks.contains(&flank) {
-                spawn_ape(&mut commands, &asset_server, &mut texture_atlases, flank);
-            }
+    apes_respawn.set_duration(Duration::from_secs_f32(tuning.respawn_delay));
+    apes_respawn.tick(time.delta());
+
+    // Keeps ticking during breathers so the next wave starts right away
+    if apes_respawn.finished() && wave.spawns_apes() && !boss_encounter.holds_arena() {
+        for slot in missing_slots {
+            spawn_ape(
+                &mut commands,
+                &asset_server,
+                &mut texture_atlases,
+                &font_handle,
+                &ape_icon,
+                &mut rng,
+                &tuning,
+                &wave,
+                &wave_tuning,
+                slot,
+            );
         }
     }
 }
 
 pub fn move_apes(
     time: Res<Time>,
-    mut apes_q: Query<(&mut Transform, &mut Moving, &Flank), With<Ape>>,
+    tuning: Res<ApeTuning>,
+    wave: Res<Wave>,
+    wave_tuning: Res<WaveTuning>,
+    player_q: Query<&Transform, (With<Player>, Without<Ape>)>,
+    mut apes_q: Query<
+        (
+            Entity,
+            &mut Transform,
+            &mut Moving,
+            &mut ApeFeint,
+            &ApeSlot,
+            &Flank,
+            &ApeKind,
+            &ApeAttackSpec,
+            &ApeEnrage,
+            &ApeTraits,
+        ),
+        (With<Ape>, Without<ApeStunned>, Without<ApeEntering>),
+    >,
 ) {
-    for (mut transform, mut moving, flank) in apes_q.iter_mut() {
-        let inc = 60. * time.delta_seconds();
+    let player_x = player_q.single().translation.x;
+    let apes_positions = apes_q
+        .iter()
+        .map(|(ape, transform, ..)| (ape, transform.translation.truncate()))
+        .collect::<Vec<_>>();
+
+    let steering = &tuning.steering;
+    for (
+        ape,
+        mut transform,
+        mut moving,
+        mut feint,
+        slot,
+        flank,
+        &kind,
+        attack_spec,
+        enrage,
+        traits,
+    ) in apes_q.iter_mut()
+    {
+        feint.tick(time.delta());
+
+        let stats = tuning.kind(kind);
+        let mut speed = stats.patrol_speed * wave.speed_scale(&wave_tuning) * traits.speed_scale();
+        if enrage.enraged {
+            speed *= tuning.enrage.patrol_speed;
+        }
+        let inc = speed * time.delta_seconds();
         let old_x = transform.translation.x;
-        match &*moving {
-            Moving::Left => {
-                if old_x - inc > flank.min() {
-                    transform.translation.x = old_x - inc;
-                } else {
-                    transform.translation.x = old_x + inc;
+
+        // Where the ape heads to and how fast
+        let distance = (player_x - old_x).abs();
+        let lasers_x = ApeAttackSpec::LASERS_X * transform.scale.x;
+        let optimal_x =
+            player_x - flank.facing_sign() * (lasers_x + attack_spec.attack_range.center());
+        let (target_x, inc) = match stats.steering {
+            ApeSteering::Patrol => {
+                if old_x <= slot.min {
                     *moving = Moving::Right;
-                }
-            }
-            Moving::Right => {
-                if old_x + inc < flank.max() {
-                    transform.translation.x = old_x + inc;
-                } else {
-                    transform.translation.x = old_x - inc;
+                } else if old_x >= slot.max {
                     *moving = Moving::Left;
                 }
+                match *moving {
+                    Moving::Left => (slot.min, inc),
+                    _ => (slot.max, inc),
+                }
+            }
+            _ if distance < steering.retreat_distance => {
+                let away = if old_x < player_x { -1. } else { 1. };
+                (
+                    player_x + away * steering.retreat_distance,
+                    inc * steering.rush_speed,
+                )
             }
-            _ => unreachable!(),
+            ApeSteering::Feint if feint.rushing(steering) => {
+                let toward = flank.facing_sign();
+                (
+                    player_x - toward * steering.retreat_distance,
+                    inc * steering.rush_speed,
+                )
+            }
+            ApeSteering::Chase | ApeSteering::Feint => (optimal_x, inc),
+        };
+
+        let new_x = (old_x + (target_x - old_x).clamp(-inc, inc)).clamp(slot.min, slot.max);
+
+        // Apes never walk into each other, perched ones are out of the way
+        let old_position = transform.translation.truncate();
+        let new_position = Vec2::new(new_x, old_position.y);
+        let blocked = apes_positions.iter().any(|&(other, other_position)| {
+            other != ape
+                && new_position.distance(other_position) < Ape::SPACING
+                && new_position.distance(other_position) < old_position.distance(other_position)
+        });
+        if blocked {
+            // Patrolling apes turn back instead of waiting
+            if stats.steering == ApeSteering::Patrol {
+                *moving = match *moving {
+                    Moving::Left => Moving::Right,
+                    _ => Moving::Left,
+                };
+            }
+            continue;
+        }
+
+        if new_x < old_x {
+            *moving = Moving::Left;
+        } else if new_x > old_x {
+            *moving = Moving::Right;
         }
+        transform.translation.x = new_x;
     }
 }
 
 pub fn trigger_ape_attack(
+    time: Res<Time>,
+    tuning: Res<ApeTuning>,
+    wave: Res<Wave>,
+    wave_tuning: Res<WaveTuning>,
+    mut rng: ResMut<GameRng>,
+    mut commands: Commands,
+    player_q: Query<&Transform, With<Player>>,
+    mut apes_q: Query<
+        (
+            Entity,
+            &Transform,
+            &ApeKind,
+            &ApeAttackSpec,
+            &ApeEnrage,
+            Option<&ApeChain>,
+            &mut ApeAttackSchedule,
+        ),
+        (
+            With<Ape>,
+            Without<Player>,
+            Without<ApeStunned>,
+            Without<ApeEntering>,
+        ),
+    >,
+    attacks_q: Query<&ApeAttackOwner>,
+    mut attacks_log: Local<ApesAttackLog>,
+) {
+    attacks_log.since_last += time.delta();
+
+    let attacking = attacks_q
+        .iter()
+        .map(|owner| **owner)
+        .collect::<HashSet<_>>();
+    let apes_count = apes_q.iter().len();
+    let player_x = player_q.single().translation.x;
+
+    // Apes only wait for their next attack once the previous one is over
+    let mut ready = vec![];
+    let mut chained = HashSet::new();
+    for (ape, transform, _, attack_spec, _, chain, mut schedule) in apes_q.iter_mut() {
+        if attacking.contains(&ape) {
+            continue;
+        }
+        // Chained attacks follow right away, regardless of the coordination
+        if let Some(&ApeChain(attack)) = chain {
+            spawn_ape_attack(
+                &mut commands,
+                ape,
+                attack,
+                attack_spec,
+                transform.translation.x,
+                player_x,
+                &tuning.attacks,
+            );
+            commands.entity(ape).remove::<ApeChain>();
+            chained.insert(ape);
+            continue;
+        }
+        if schedule.tick(time.delta()).finished() {
+            ready.push(ape);
+        }
+    }
+    if ready.is_empty() {
+        return;
+    }
+
+    let attackers = match tuning.coordination {
+        ApesCoordination::Independent => ready,
+        ApesCoordination::Alternate => ready
+            .into_iter()
+            .filter(|&ape| apes_count == 1 || attacks_log.last != Some(ape))
+            .take(1)
+            .collect(),
+        ApesCoordination::Pincer => apes_q
+            .iter()
+            .map(|(ape, ..)| ape)
+            .filter(|ape| !attacking.contains(ape) && !chained.contains(ape))
+            .collect(),
+        ApesCoordination::Staggered => {
+            if attacks_log.since_last.as_secs_f32() < tuning.stagger {
+                return;
+            }
+            ready.into_iter().take(1).collect()
+        }
+    };
+
+    let interval = wave.attack_interval(&wave_tuning, tuning.attack_interval);
+    for ape in attackers {
+        let Ok((_, transform, &kind, attack_spec, enrage, _, mut schedule)) = apes_q.get_mut(ape)
+        else {
+            continue;
+        };
+        let attacks = &tuning.kind(kind).attacks;
+        let attack = *attacks.choose(&mut *rng).unwrap();
+        spawn_ape_attack(
+            &mut commands,
+            ape,
+            attack,
+            attack_spec,
+            transform.translation.x,
+            player_x,
+            &tuning.attacks,
+        );
+        if enrage.enraged && rng.gen::<f32>() < tuning.enrage.chain_chance {
+            let chained = *attacks.choose(&mut *rng).unwrap();
+            commands.entity(ape).insert(ApeChain(chained));
+        }
+        schedule.reschedule(&mut rng, interval, &tuning);
+        attacks_log.last = Some(ape);
+        attacks_log.since_last = Duration::ZERO;
+    }
+}
+
+/// Cancels the charging attacks of apes taking enough damages, and stuns them.
+pub fn interrupt_apes(
+    tuning: Res<ApeTuning>,
+    mut commands: Commands,
+    mut apes_q: Query<(Entity, &ApeKind, &mut ApeInterrupt, &mut Sprite), With<Ape>>,
+    attacks_q: Query<(Entity, &ApeAttackOwner, &StagedAnimation)>,
+) {
+    for (ape, &kind, mut interrupt, mut sprite) in apes_q.iter_mut() {
+        let charges = attacks_q
+            .iter()
+            .filter(|(_, &owner, anim)| {
+                *owner == ape && matches!(anim, StagedAnimation::Init { .. })
+            })
+            .map(|(attack, ..)| attack)
+            .collect::<Vec<_>>();
+
+        // Only damages taken during a charge count
+        if charges.is_empty() {
+            interrupt.damages = 0.;
+            continue;
+        }
+        if interrupt.damages < tuning.kind(kind).interrupt_threshold {
+            continue;
+        }
+
+        for attack in charges {
+            commands.entity(attack).despawn_recursive();
+        }
+        interrupt.damages = 0.;
+        sprite.color = ApeStunned::TINT;
+        commands
+            .entity(ape)
+            .remove::<ApeChain>()
+            .insert(ApeStunned(Timer::from_seconds(
+                tuning.stun_duration,
+                TimerMode::Once,
+            )));
+    }
+}
+
+pub fn recover_stunned_apes(
     time: Res<Time>,
     mut commands: Commands,
-    apes_q: Query<(Entity, &ApeAttackSpec), With<Ape>>,
-    mut trigger: Local<TriggerTimer>,
+    mut apes_q: Query<
+        (
+            Entity,
+            &mut ApeStunned,
+            &mut Sprite,
+            Option<&ApeEnrage>,
+            Option<&ApeTraits>,
+        ),
+        With<Ape>,
+    >,
 ) {
-    trigger.tick(time.delta());
-    if trigger.just_finished() {
-        for (ape, attack_spec) in apes_q.iter() {
-            spawn_ape_attack_init(&mut commands, ape, attack_spec);
+    for (ape, mut stunned, mut sprite, enrage, traits) in apes_q.iter_mut() {
+        if stunned.tick(time.delta()).finished() {
+            let fur = traits.map_or(Color::WHITE, |traits| traits.fur.color());
+            sprite.color = enrage.map_or(fur, |enrage| enrage.tint(fur));
+            commands.entity(ape).remove::<ApeStunned>();
+        }
+    }
+}
+
+pub fn enrage_apes(
+    tuning: Res<ApeTuning>,
+    mut apes_q: Query<
+        (
+            &ApeLife,
+            &ApeKind,
+            &ApeTraits,
+            &mut ApeEnrage,
+            &mut ApeAttackSpec,
+            &mut Sprite,
+            Has<ApeStunned>,
+        ),
+        (With<Ape>, Changed<ApeLife>),
+    >,
+) {
+    for (ape_life, &kind, traits, mut enrage, mut attack_spec, mut sprite, stunned) in
+        apes_q.iter_mut()
+    {
+        if enrage.enraged || !enrage.reached_by(ape_life) {
+            continue;
+        }
+
+        enrage.enraged = true;
+        let charge = tuning.laser_charge(kind, true) * traits.laser_charge_scale();
+        attack_spec.init_duration = DurationTimer::from_seconds(charge);
+        // Stunned apes get their tint back once recovered
+        if !stunned {
+            sprite.color = ApeEnrage::TINT;
+        }
+    }
+}
+
+pub fn animate_apes_entrances(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut apes_q: Query<(
+        Entity,
+        &mut ApeEntering,
+        &mut Transform,
+        &mut Sprite,
+        &ApeTraits,
+        Option<&ApeHealthBar>,
+    )>,
+) {
+    for (ape, mut entering, mut transform, mut sprite, traits, health_bar) in apes_q.iter_mut() {
+        entering.timer.tick(time.delta());
+        transform.translation = entering.position();
+        sprite.color = entering.color(traits.fur.color());
+
+        if entering.timer.finished() {
+            sprite.color = traits.fur.color();
+            if entering.entrance == ApeEntrance::Drop {
+                let feet = entering.to.truncate() + Vec2::Y * ApeEntering::FEET_Y;
+                spawn_landing_dust(&mut commands, feet);
+            }
+            if let Some(health_bar) = health_bar {
+                commands
+                    .entity(health_bar.root)
+                    .insert(Visibility::Inherited);
+            }
+            commands.entity(ape).remove::<ApeEntering>();
+        }
+    }
+}
+
+pub fn update_apes_health_bars(
+    apes_q: Query<
+        (&ApeLife, &ApeEnrage, &ApeHealthBar),
+        Or<(Changed<ApeLife>, Changed<ApeEnrage>)>,
+    >,
+    mut fills_q: Query<(&mut Transform, &mut Fill), With<ApeHealthFill>>,
+    mut trails_q: Query<&mut ApeHealthTrail>,
+    mut marks_q: Query<
+        (&mut Transform, &mut Visibility),
+        (With<EnrageMark>, Without<ApeHealthFill>),
+    >,
+) {
+    for (ape_life, enrage, health_bar) in apes_q.iter() {
+        let left = ape_life.current / ape_life.max;
+
+        if let Ok((mut transform, mut fill)) = fills_q.get_mut(health_bar.fill) {
+            transform.scale.x = left;
+            fill.color = if enrage.enraged {
+                Color::RED
+            } else {
+                Color::PINK
+            };
+        }
+
+        if let Ok(mut trail) = trails_q.get_mut(health_bar.trail) {
+            if left < trail.target {
+                trail.delay.reset();
+            }
+            trail.target = left;
+            trail.shown = trail.shown.max(left);
+        }
+
+        if let Ok((mut transform, mut visibility)) = marks_q.get_mut(health_bar.mark) {
+            transform.translation.x = (enrage.below - 0.5) * ApeHealthBar::WIDTH;
+            *visibility = if enrage.below > 0. && !enrage.enraged {
+                Visibility::Inherited
+            } else {
+                Visibility::Hidden
+            };
+        }
+    }
+}
+
+pub fn animate_apes_health_trails(
+    time: Res<Time>,
+    mut trails_q: Query<(&mut ApeHealthTrail, &mut Transform)>,
+) {
+    for (mut trail, mut transform) in trails_q.iter_mut() {
+        if trail.delay.tick(time.delta()).finished() {
+            let caught_up = trail.shown - ApeHealthTrail::SPEED * time.delta_seconds();
+            trail.shown = caught_up.max(trail.target);
+        }
+        transform.scale.x = trail.shown;
+    }
+}
+
+pub fn animate_landing_dust(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut dust_q: Query<(Entity, &mut LandingDust, &mut Transform, &mut Fill)>,
+) {
+    for (id, mut dust, mut transform, mut fill) in dust_q.iter_mut() {
+        dust.tick(time.delta());
+        if dust.finished() {
+            commands.entity(id).despawn_recursive();
+        } else {
+            transform.scale.x = 1. + 2. * dust.percent();
+            fill.color.set_a(0.8 * dust.percent_left());
         }
     }
 }
@@ -436,7 +1427,8 @@ pub fn trigger_ape_attack(
 pub fn ape_attacks_player_collision(
     mut commands: Commands,
     mut ev_unit_changed: EventWriter<UnitChanged>,
-    attacks_q: Query<(&GlobalTransform, &ApeAttackRange, &Flank)>,
+    attacks_q: Query<(&GlobalTransform, &ApeAttackRange, &Flank, &ApeAttackOwner)>,
+    slots_q: Query<&ApeSlot>,
     player_q: Query<(Entity, &Transform, &UnitState, &UnitCondition), With<Player>>,
     mut health_q: Query<&mut LifeChunks, With<LifeHud>>,
 ) {
@@ -446,46 +1438,84 @@ pub fn ape_attacks_player_collision(
         return;
     }
 
-    if matches!(
-        player_state,
-        UnitState::Dash | UnitState::Jump | UnitState::Fall
-    ) {
+    if matches!(player_state, UnitState::Dash) {
         return;
     }
+    let airborne = matches!(player_state, UnitState::Jump | UnitState::Fall);
 
     let player_x = player_transform.translation.x;
-    for (attack_transform, &ApeAttackRange { offset_x, range_x }, flank) in attacks_q.iter() {
+    let player_y = player_transform.translation.y;
+    for (attack_transform, &ApeAttackRange { offset_x, range_x }, flank, owner) in attacks_q.iter()
+    {
         let attack_x = attack_transform.to_scale_rotation_translation().2.x;
 
-        let player_in_range = match flank {
-            Flank::Left => {
-                attack_x + offset_x < player_x && player_x < attack_x + offset_x + range_x
-            }
-            Flank::Right => {
-                attack_x - offset_x - range_x < player_x && player_x < attack_x - offset_x
-            }
-        };
+        // The range starts after the offset, on the side the ape faces
+        let near_x = attack_x + flank.facing_sign() * offset_x;
+        let far_x = near_x + flank.facing_sign() * range_x;
+        let player_in_range = near_x.min(far_x) < player_x && player_x < near_x.max(far_x);
 
-        if player_in_range && !matches!(player_state, UnitState::Wound | UnitState::Die) {
-            let mut health_chunks = health_q.single_mut();
-            if let Some(chunk) = health_chunks.pop() {
-                commands.entity(chunk).despawn();
-            }
+        // Lasers from the floor are jumped over, those of perched apes only hit when jumping
+        let perch = slots_q.get(**owner).ok().and_then(|slot| slot.perch);
+        let player_at_height = match perch {
+            Some(perch) => ApeAttackRange::level_with_perch(perch, player_y),
+            None => !airborne,
+        };
 
-            if health_chunks.is_empty() {
-                ev_unit_changed.send(UnitChanged::entity(player).new_state(UnitState::Die));
-            } else {
-                ev_unit_changed.send(UnitChanged::entity(player).new_state(UnitState::Wound));
-            }
+        if player_in_range
+            && player_at_height
+            && !matches!(player_state, UnitState::Wound | UnitState::Die)
+        {
+            wound_player(
+                &mut commands,
+                &mut ev_unit_changed,
+                player,
+                &mut health_q.single_mut(),
+            );
         }
     }
 }
 
+/// Takes a life chunk from the player, the last one kills it.
+pub fn wound_player(
+    commands: &mut Commands,
+    ev_unit_changed: &mut EventWriter<UnitChanged>,
+    player: Entity,
+    health_chunks: &mut LifeChunks,
+) {
+    if let Some(chunk) = health_chunks.pop() {
+        commands.entity(chunk).despawn();
+    }
+
+    if health_chunks.is_empty() {
+        ev_unit_changed.send(UnitChanged::entity(player).new_state(UnitState::Die));
+    } else {
+        ev_unit_changed.send(UnitChanged::entity(player).new_state(UnitState::Wound));
+    }
+}
+
+/// Removes `damages` from an ape life and plays its wound animation.
+pub fn wound_ape(
+    commands: &mut Commands,
+    ape: Entity,
+    ape_life: &mut ApeLife,
+    damages: f32,
+    wound_h: &ApeWoundHandle,
+    flank: &Flank,
+    interrupt: Option<&mut ApeInterrupt>,
+) {
+    ape_life.decrease_by(damages);
+    if let Some(interrupt) = interrupt {
+        interrupt.damages += damages;
+    }
+    let wound_anim = spawn_ape_wound_anim(commands, ape_life, wound_h, flank);
+    commands.entity(ape).push_children(&[wound_anim]);
+}
+
 pub fn animate_apes_attacks(
     time: Res<Time>,
     mut commands: Commands,
     texture_atlases: Res<Assets<TextureAtlas>>,
-    apes_q: Query<&ApeAttackSpec, With<Ape>>,
+    apes_q: Query<&ApeAttackSpec, Or<(With<Ape>, With<Boss>)>>,
     mut attacks_anim_q: Query<(
         Entity,
         &Parent,
@@ -531,9 +1561,17 @@ pub fn animate_apes_attacks(
 pub fn animate_apes_wounds(
     time: Res<Time>,
     texture_atlases: Res<Assets<TextureAtlas>>,
+    tuning: Res<ApeTuning>,
+    boss_tuning: Res<BossTuning>,
+    eth_tuning: Res<EthTuning>,
+    eth_handle: Res<EthHandle>,
+    asset_server: Res<AssetServer>,
+    mut rng: ResMut<GameRng>,
     mut commands: Commands,
     mut score: ResMut<Score>,
+    mut ev_ape_killed: EventWriter<ApeKilled>,
     mut dead_counter: Query<&mut DeadApesCounter, With<DeadApesHud>>,
+    apes_q: Query<(&Transform, &ApeKind, Option<&ApeTraits>, Has<Boss>)>,
     mut wounds_q: Query<(
         Entity,
         &Parent,
@@ -560,7 +1598,45 @@ pub fn animate_apes_wounds(
                 else if life.current == 0. {
                     dead_counter.single_mut().0 += 1;
                     score.0 += 1;
-                    commands.entity(ape.get()).despawn_recursive();
+                    let ape = ape.get();
+                    let Ok((transform, &kind, traits, is_boss)) = apes_q.get(ape) else {
+                        continue;
+                    };
+                    let position = transform.translation.truncate();
+                    let rest_y = if is_boss {
+                        spawn_boss_loot(
+                            &mut commands,
+                            position.x,
+                            &boss_tuning,
+                            &eth_tuning,
+                            &eth_handle,
+                        );
+                        Boss::Y
+                    } else {
+                        let loot = &tuning.kind(kind).loot;
+                        let loot = traits.map_or(loot.clone(), |traits| traits.loot(loot));
+                        spawn_loot(
+                            &mut commands,
+                            &asset_server,
+                            &eth_handle,
+                            &mut rng,
+                            position,
+                            &loot,
+                        );
+                        if let Some(&traits) = traits {
+                            ev_ape_killed.send(ApeKilled { kind, traits });
+                        }
+                        0.
+                    };
+                    spawn_dead_icons_burst(&mut commands, &asset_server, position);
+
+                    // No longer an ape for the game, its bars, attacks or ledge go away
+                    commands
+                        .entity(ape)
+                        .remove::<(Ape, Boss, ApeStunned)>()
+                        .insert(ApeDeath::new(rest_y))
+                        .insert(Gravity::default())
+                        .despawn_descendants();
                 } else {
                     commands.entity(anim_id).despawn_recursive();
                 }
@@ -570,6 +1646,56 @@ pub fn animate_apes_wounds(
     }
 }
 
+pub fn animate_apes_deaths(
+    time: Res<Time>,
+    tuning: Res<PlayerTuning>,
+    mut commands: Commands,
+    mut dying_q: Query<(
+        Entity,
+        &mut ApeDeath,
+        &mut Transform,
+        &mut Gravity,
+        &mut Sprite,
+        &Flank,
+    )>,
+) {
+    for (ape, mut death, mut transform, mut gravity, mut sprite, flank) in dying_q.iter_mut() {
+        death.timer.tick(time.delta());
+        if death.timer.finished() {
+            commands.entity(ape).despawn_recursive();
+            continue;
+        }
+
+        // Falls on its back during the first half, then fades away
+        let progress = death.timer.percent();
+        let collapse = (progress * 2.).min(1.);
+        transform.rotation =
+            Quat::from_rotation_z(flank.facing_sign() * collapse * std::f32::consts::FRAC_PI_2);
+        sprite.color.set_a(1. - ((progress - 0.5) * 2.).max(0.));
+
+        if transform.translation.y > death.rest_y {
+            gravity.fall(&mut transform, tuning.gravity, time.delta_seconds());
+            transform.translation.y = transform.translation.y.max(death.rest_y);
+        }
+    }
+}
+
+pub fn animate_dead_icons(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut icons_q: Query<(Entity, &mut DeadIcon, &mut Transform, &mut Sprite)>,
+) {
+    for (id, mut icon, mut transform, mut sprite) in icons_q.iter_mut() {
+        icon.timer.tick(time.delta());
+        if icon.timer.finished() {
+            commands.entity(id).despawn_recursive();
+            continue;
+        }
+        transform.translation += (icon.velocity * time.delta_seconds()).extend(0.);
+        sprite.color.set_a(icon.timer.percent_left());
+    }
+}
+
 pub fn display_dead_apes_hud(
     counter: Query<&DeadApesCounter>,
     mut text_q: Query<&mut Text, With<DeadApesText>>,
@@ -577,3 +1703,36 @@ pub fn display_dead_apes_hud(
     let mut text = text_q.single_mut();
     text.sections[0].value = counter.single().to_string();
 }
+
+pub fn retune_apes(
+    tuning: Res<ApeTuning>,
+    wave: Res<Wave>,
+    wave_tuning: Res<WaveTuning>,
+    mut apes_q: Query<
+        (
+            &mut ApeLife,
+            &mut ApeAttackSpec,
+            &mut ApeFeint,
+            &mut ApeEnrage,
+            &ApeKind,
+            &ApeTraits,
+        ),
+        With<Ape>,
+    >,
+) {
+    if !tuning.is_changed() && !wave_tuning.is_changed() {
+        return;
+    }
+
+    for (mut ape_life, mut attack_spec, mut feint, mut enrage, &kind, traits) in apes_q.iter_mut() {
+        feint.set_duration(Duration::from_secs_f32(tuning.steering.feint_interval));
+        let stats = tuning.kind(kind);
+        enrage.below = stats.enrage_below;
+        ape_life.rescale(stats.life * wave.life_scale(&wave_tuning) * traits.life_scale());
+        attack_spec.attack_range = ApeAttackRange::lasers(stats.laser_reach);
+        attack_spec.reach = stats.laser_reach;
+        let charge = tuning.laser_charge(kind, enrage.enraged) * traits.laser_charge_scale();
+        attack_spec.init_duration = DurationTimer::from_seconds(charge);
+        attack_spec.on_duration = DurationTimer::from_seconds(stats.laser_fire);
+    }
+}
@@ -0,0 +1,392 @@
+use crate::prelude::*;
+
+//////////////////////////////////////// Plugin ////////////////////////////////////////
+
+pub struct AttacksPlugin;
+
+impl Plugin for AttacksPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                animate_ape_attacks_stages,
+                move_ape_attacks,
+                ape_hitboxes_player_collision.after(move_ape_attacks),
+            )
+                .run_if(in_state(AppState::InGame)),
+        );
+    }
+}
+
+/////////////////////////////////////// Spawners ///////////////////////////////////////
+
+/// Starts the telegraph of `attack`, the attack itself follows once it is over.
+pub fn spawn_ape_attack(
+    commands: &mut Commands,
+    ape: Entity,
+    attack: ApeAttackKind,
+    attack_spec: &ApeAttackSpec,
+    ape_x: f32,
+    player_x: f32,
+    tuning: &ApeAttacksTuning,
+) {
+    let facing = attack_spec.flank.facing_sign();
+
+    let (shape, origin_x, color, duration) = match attack {
+        ApeAttackKind::Lasers => {
+            spawn_ape_attack_init(commands, ape, attack_spec);
+            return;
+        }
+        // Dust rising under the ape
+        ApeAttackKind::Slam => (
+            GeometryBuilder::new().add(&shapes::Ellipse {
+                radii: Vec2::new(90., 12.),
+                center: Vec2::ZERO,
+            }),
+            ape_x,
+            Color::rgba(0.9, 0.6, 0.2, 0.7),
+            tuning.slam_charge,
+        ),
+        // The floor path of the sweep
+        ApeAttackKind::Sweep => {
+            let length = tuning.sweep_speed * tuning.sweep_duration;
+            (
+                GeometryBuilder::new().add(&shapes::Rectangle {
+                    extents: Vec2::new(length, 4.),
+                    origin: shapes::RectangleOrigin::CustomCenter(Vec2::new(
+                        facing * length / 2.,
+                        0.,
+                    )),
+                }),
+                ape_x + facing * ApeAttackKind::SWEEP_START,
+                Color::rgba(1., 0.1, 0.1, 0.5),
+                tuning.sweep_charge,
+            )
+        }
+        // Where the beam will fall
+        ApeAttackKind::Beam => (
+            GeometryBuilder::new().add(&shapes::Rectangle {
+                extents: Vec2::new(tuning.beam_width, 8.),
+                origin: shapes::RectangleOrigin::Center,
+            }),
+            player_x,
+            Color::rgba(0.4, 0.9, 1., 0.6),
+            tuning.beam_charge,
+        ),
+        // Where the projectile will land
+        ApeAttackKind::Throw => (
+            GeometryBuilder::new().add(&shapes::Circle {
+                radius: 16.,
+                center: Vec2::ZERO,
+            }),
+            player_x,
+            Color::rgba(0.6, 0.4, 0.2, 0.7),
+            tuning.throw_charge,
+        ),
+    };
+
+    commands.spawn((
+        ShapeBundle {
+            path: shape.build(),
+            spatial: SpatialBundle {
+                transform: Transform::from_xyz(origin_x, ApeAttackKind::FLOOR, 15.),
+                ..default()
+            },
+            ..default()
+        },
+        Fill::color(color),
+        attack,
+        ApeAttackOwner(ape),
+        ApeAttackOrigin {
+            kind: attack_spec.kind,
+            ape_x,
+            target_x: origin_x,
+            facing,
+        },
+        StagedAnimation::init(
+            DurationTimer::from_seconds(duration),
+            Timer::from_seconds(0.1, TimerMode::Repeating),
+        ),
+    ));
+}
+
+fn spawn_ape_attack_hits(
+    commands: &mut Commands,
+    ape: Entity,
+    attack: ApeAttackKind,
+    origin: &ApeAttackOrigin,
+    tuning: &ApeAttacksTuning,
+    gravity: f32,
+) {
+    let floor = ApeAttackKind::FLOOR;
+    let mut spawn_hit = |shape: GeometryBuilder,
+                         position: Vec2,
+                         color: Color,
+                         duration: f32,
+                         motion: Vec2,
+                         hitbox: ApeHitbox| {
+        let mut hit = commands.spawn((
+            ShapeBundle {
+                path: shape.build(),
+                spatial: SpatialBundle {
+                    transform: Transform::from_translation(position.extend(15.)),
+                    ..default()
+                },
+                ..default()
+            },
+            Fill::color(color),
+            attack,
+            ApeAttackOwner(ape),
+            hitbox,
+            StagedAnimation::on(
+                DurationTimer::from_seconds(duration),
+                Timer::from_seconds(0.1, TimerMode::Repeating),
+            ),
+        ));
+        if motion != Vec2::ZERO {
+            hit.insert(ApeAttackMotion { velocity: motion });
+        }
+    };
+
+    match attack {
+        ApeAttackKind::Lasers => unreachable!(),
+        // Two waves rolling away from the ape
+        ApeAttackKind::Slam => {
+            for direction in [-1., 1.] {
+                spawn_hit(
+                    GeometryBuilder::new().add(&shapes::Rectangle {
+                        extents: Vec2::new(24., 40.),
+                        origin: shapes::RectangleOrigin::CustomCenter(Vec2::new(0., 20.)),
+                    }),
+                    Vec2::new(origin.ape_x, floor),
+                    Color::rgb(0.9, 0.6, 0.2),
+                    tuning.slam_duration,
+                    Vec2::new(direction * tuning.slam_speed, 0.),
+                    ApeHitbox {
+                        half_width: 20.,
+                        dodged_by: Dodge::Jump,
+                    },
+                );
+            }
+        }
+        ApeAttackKind::Sweep => spawn_hit(
+            GeometryBuilder::new().add(&shapes::Rectangle {
+                extents: Vec2::new(40., 16.),
+                origin: shapes::RectangleOrigin::Center,
+            }),
+            Vec2::new(origin.target_x, floor),
+            Color::rgb(1., 0.1, 0.1),
+            tuning.sweep_duration,
+            Vec2::new(origin.facing * tuning.sweep_speed, 0.),
+            ApeHitbox {
+                half_width: 20.,
+                dodged_by: Dodge::JumpOrDash,
+            },
+        ),
+        ApeAttackKind::Beam => spawn_hit(
+            GeometryBuilder::new().add(&shapes::Rectangle {
+                extents: Vec2::new(tuning.beam_width, GLOBAL_HEIGHT),
+                origin: shapes::RectangleOrigin::CustomCenter(Vec2::new(0., GLOBAL_HEIGHT / 2.)),
+            }),
+            Vec2::new(origin.target_x, floor),
+            Color::rgba(0.4, 0.9, 1., 0.8),
+            tuning.beam_duration,
+            Vec2::ZERO,
+            ApeHitbox {
+                half_width: tuning.beam_width / 2.,
+                dodged_by: Dodge::Dash,
+            },
+        ),
+        // Thrown from the ape head, lands on the target after the flight duration
+        ApeAttackKind::Throw => {
+            let start = Vec2::new(origin.ape_x, 40.);
+            let flight = tuning.throw_flight;
+            let velocity = Vec2::new(
+                (origin.target_x - start.x) / flight,
+                (floor - start.y) / flight + gravity * flight / 2.,
+            );
+            spawn_projectile(
+                commands,
+                ape,
+                ProjectileKind::thrown_by(origin.kind),
+                start,
+                velocity,
+            );
+        }
+    }
+}
+
+////////////////////////////////////// Components //////////////////////////////////////
+
+/// The attacks apes can pick from, each one is dodged differently.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Component, Deserialize)]
+pub enum ApeAttackKind {
+    /// Horizontal eyes lasers, jump or dash through them
+    Lasers,
+    /// Shockwaves rolling on the floor, jump over them
+    Slam,
+    /// A laser spot running along the floor
+    Sweep,
+    /// A column falling on the player, dash through it
+    Beam,
+    /// A banana or a token thrown at the player, dash through it or hit it back
+    Throw,
+}
+
+impl ApeAttackKind {
+    /// Height of the platform surface
+    pub const FLOOR: f32 = -240.;
+    /// Distance from the ape where the sweep starts
+    const SWEEP_START: f32 = 100.;
+}
+
+/// The ape that started an attack, apes wait for their attack to be over to schedule the next.
+#[derive(Clone, Copy, Component, Deref)]
+pub struct ApeAttackOwner(pub Entity);
+
+/// Where an attack was aimed when its telegraph started.
+#[derive(Clone, Copy, Component)]
+pub struct ApeAttackOrigin {
+    /// The kind of the ape attacking
+    pub kind: ApeKind,
+    pub ape_x: f32,
+    pub target_x: f32,
+    /// 1 when the ape faces right, -1 otherwise
+    pub facing: f32,
+}
+
+#[derive(Clone, Copy, Component)]
+pub struct ApeAttackMotion {
+    pub velocity: Vec2,
+}
+
+/// The area an attack wounds the player in, centered on the attack.
+#[derive(Clone, Copy, Component)]
+pub struct ApeHitbox {
+    pub half_width: f32,
+    pub dodged_by: Dodge,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Dodge {
+    Jump,
+    Dash,
+    JumpOrDash,
+}
+
+impl Dodge {
+    pub fn dodges(&self, state: UnitState) -> bool {
+        let jumping = matches!(state, UnitState::Jump | UnitState::Fall);
+        let dashing = matches!(state, UnitState::Dash);
+        match self {
+            Self::Jump => jumping,
+            Self::Dash => dashing,
+            Self::JumpOrDash => jumping || dashing,
+        }
+    }
+}
+
+/////////////////////////////////////// Systems ////////////////////////////////////////
+
+pub fn animate_ape_attacks_stages(
+    time: Res<Time>,
+    tuning: Res<ApeTuning>,
+    player_tuning: Res<PlayerTuning>,
+    mut commands: Commands,
+    apes_q: Query<(), Or<(With<Ape>, With<Boss>)>>,
+    mut attacks_q: Query<(
+        Entity,
+        &ApeAttackKind,
+        &ApeAttackOwner,
+        Option<&ApeAttackOrigin>,
+        &mut StagedAnimation,
+        &mut Visibility,
+    )>,
+) {
+    for (id, &attack, &owner, origin, mut anim, mut visibility) in attacks_q.iter_mut() {
+        match &mut *anim {
+            StagedAnimation::Init { duration, timer } => {
+                // The attack is called off with its ape
+                if apes_q.get(*owner).is_err() {
+                    commands.entity(id).despawn_recursive();
+                    continue;
+                }
+
+                duration.tick(time.delta());
+                timer.tick(time.delta());
+
+                if duration.finished() {
+                    commands.entity(id).despawn_recursive();
+                    if let Some(origin) = origin {
+                        spawn_ape_attack_hits(
+                            &mut commands,
+                            *owner,
+                            attack,
+                            origin,
+                            &tuning.attacks,
+                            player_tuning.gravity,
+                        );
+                    }
+                } else if timer.just_finished() {
+                    *visibility = match *visibility {
+                        Visibility::Hidden => Visibility::Visible,
+                        _ => Visibility::Hidden,
+                    };
+                }
+            }
+            StagedAnimation::On { duration, .. } => {
+                duration.tick(time.delta());
+                if duration.finished() {
+                    commands.entity(id).despawn_recursive();
+                }
+            }
+        }
+    }
+}
+
+pub fn move_ape_attacks(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut attacks_q: Query<(Entity, &mut Transform, &ApeAttackMotion)>,
+) {
+    for (id, mut transform, motion) in attacks_q.iter_mut() {
+        transform.translation += (motion.velocity * time.delta_seconds()).extend(0.);
+
+        let wall = GLOBAL_WIDTH / 2.;
+        if transform.translation.x.abs() > wall {
+            commands.entity(id).despawn_recursive();
+        }
+    }
+}
+
+pub fn ape_hitboxes_player_collision(
+    mut commands: Commands,
+    mut ev_unit_changed: EventWriter<UnitChanged>,
+    hitboxes_q: Query<(&GlobalTransform, &ApeHitbox)>,
+    player_q: Query<(Entity, &Transform, &UnitState, &UnitCondition), With<Player>>,
+    mut health_q: Query<&mut LifeChunks, With<LifeHud>>,
+) {
+    let (player, player_transform, &player_state, player_condition) = player_q.single();
+
+    if matches!(player_condition, UnitCondition::Upgraded)
+        || matches!(player_state, UnitState::Wound | UnitState::Die)
+    {
+        return;
+    }
+
+    for (hit_transform, hitbox) in hitboxes_q.iter() {
+        // Attacks on the floor hit the player whatever its height
+        let distance = (player_transform.translation.x - hit_transform.translation().x).abs();
+        if distance >= hitbox.half_width || hitbox.dodged_by.dodges(player_state) {
+            continue;
+        }
+
+        wound_player(
+            &mut commands,
+            &mut ev_unit_changed,
+            player,
+            &mut health_q.single_mut(),
+        );
+        return;
+    }
+}
@@ -0,0 +1,543 @@
+use crate::prelude::*;
+
+//////////////////////////////////////// Plugin ////////////////////////////////////////
+
+pub struct BossPlugin;
+
+impl Plugin for BossPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BossEncounter>().add_systems(
+            Update,
+            (
+                summon_boss,
+                move_boss,
+                trigger_boss_attack,
+                boss_charge_collision,
+                display_boss_hud,
+                retune_boss,
+            )
+                .run_if(in_state(AppState::InGame)),
+        );
+    }
+}
+
+/////////////////////////////////////// Spawners ///////////////////////////////////////
+
+pub fn spawn_boss(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    texture_atlases: &mut Assets<TextureAtlas>,
+    rng: &mut GameRng,
+    tuning: &BossTuning,
+) {
+    let kind = *ApeKind::ALL.choose(rng).unwrap();
+    let flank = Flank::Left;
+
+    let mut attack_spec = ApeAttackSpec::lasers(
+        asset_server,
+        texture_atlases,
+        kind,
+        tuning.laser_reach,
+        tuning.laser_charge,
+        tuning.laser_fire,
+        flank,
+    );
+    attack_spec.attack_range = Boss::lasers(tuning.laser_reach);
+
+    commands
+        .spawn(Boss)
+        .insert(SpriteBundle {
+            texture: asset_server.load(format!("{}.png", kind.name())),
+            transform: Transform {
+                scale: Vec3::splat(Boss::SCALE),
+                translation: Vec3::new(0., Boss::Y, 6.),
+                ..default()
+            },
+            sprite: Sprite {
+                flip_x: flank.flip_x(),
+                ..default()
+            },
+            ..default()
+        })
+        .insert(ApeWoundHandle::load(asset_server, texture_atlases, kind))
+        .insert(ApeWoundWidth(170. * Boss::SCALE))
+        .insert(ApeLife::new(tuning.life))
+        .insert(kind)
+        .insert(flank)
+        .insert(Moving::Right)
+        .insert(BossPattern::new(tuning.attack_interval))
+        .insert(attack_spec);
+}
+
+pub fn spawn_boss_hud(commands: &mut Commands, font_handle: &Handle<Font>, tuning: &BossTuning) {
+    let (width, height) = (500., 14.);
+
+    let outer_rect = shapes::Rectangle {
+        extents: Vec2::new(width, height),
+        origin: shapes::RectangleOrigin::TopLeft,
+    };
+    let builder = GeometryBuilder::new().add(&outer_rect);
+    let boss_hud = commands
+        .spawn(BossHud)
+        .insert((
+            ShapeBundle {
+                path: builder.build(),
+                spatial: SpatialBundle {
+                    transform: Transform::from_xyz(-width / 2., 250., 999.),
+                    ..default()
+                },
+                ..default()
+            },
+            Fill::color(Color::NONE),
+            Stroke::new(Color::WHITE, 3.),
+        ))
+        .id();
+
+    let inner_rect = shapes::Rectangle {
+        extents: Vec2::new(width - 3., height - 3.),
+        origin: shapes::RectangleOrigin::TopLeft,
+    };
+    let builder = GeometryBuilder::new().add(&inner_rect);
+    let gauge = commands
+        .spawn((
+            ShapeBundle {
+                path: builder.build(),
+                spatial: SpatialBundle {
+                    transform: Transform::from_xyz(3. / 2., -3. / 2., 0.),
+                    ..default()
+                },
+                ..default()
+            },
+            Fill::color(Color::CRIMSON),
+        ))
+        .insert(BossGauge)
+        .id();
+    commands.entity(boss_hud).push_children(&[gauge]);
+
+    // Where each phase starts
+    for phase in 1..tuning.phases {
+        let mark_rect = shapes::Rectangle {
+            extents: Vec2::new(2., height),
+            origin: shapes::RectangleOrigin::TopLeft,
+        };
+        let builder = GeometryBuilder::new().add(&mark_rect);
+        let mark_x = width * (1. - phase as f32 / tuning.phases as f32);
+        let mark = commands
+            .spawn((
+                ShapeBundle {
+                    path: builder.build(),
+                    spatial: SpatialBundle {
+                        transform: Transform::from_xyz(mark_x, 0., 1.),
+                        ..default()
+                    },
+                    ..default()
+                },
+                Fill::color(Color::WHITE),
+            ))
+            .id();
+        commands.entity(boss_hud).push_children(&[mark]);
+    }
+
+    let text = commands
+        .spawn(Text2dBundle {
+            text: Text::from_section(
+                "",
+                TextStyle {
+                    font: font_handle.clone(),
+                    font_size: 30.0,
+                    color: Color::WHITE,
+                },
+            )
+            .with_alignment(TextAlignment::Center),
+            transform: Transform::from_xyz(width / 2., 20., 0.),
+            ..default()
+        })
+        .insert(BossText)
+        .id();
+    commands.entity(boss_hud).push_children(&[text]);
+}
+
+/// Spreads the boss reward on the floor around `x`, in pieces of the usual ETH quantity.
+pub fn spawn_boss_loot(
+    commands: &mut Commands,
+    x: f32,
+    tuning: &BossTuning,
+    eth_tuning: &EthTuning,
+    eth_handle: &EthHandle,
+) {
+    let pieces = (tuning.reward / eth_tuning.quantity).ceil().max(1.) as usize;
+    let quantity = tuning.reward / pieces as f32;
+    let wall = GLOBAL_WIDTH / 2. - 60.;
+
+    for piece in 0..pieces {
+        let offset = (piece as f32 - (pieces - 1) as f32 / 2.) * 60.;
+        let position = Vec3::new((x + offset).clamp(-wall, wall), -222., 20.);
+        spawn_eth(commands, position, quantity, eth_handle);
+    }
+}
+
+////////////////////////////////////// Components //////////////////////////////////////
+
+/// A giant ape alone in the arena, it patrols from wall to wall and faces the player.
+#[derive(Component)]
+pub struct Boss;
+
+impl Boss {
+    pub const SCALE: f32 = 1.2;
+    /// Keeps its feet on the platform despite the bigger sprite
+    pub const Y: f32 = (Self::SCALE - 0.8) * 300.;
+    /// Distance at which a charging boss runs over the player
+    pub const CONTACT_WIDTH: f32 = 120.;
+
+    pub fn min() -> f32 {
+        -(GLOBAL_WIDTH / 2. - 210. * Self::SCALE)
+    }
+
+    pub fn max() -> f32 {
+        GLOBAL_WIDTH / 2. - 210. * Self::SCALE
+    }
+
+    /// Range of the boss lasers, bigger than regular ones as they follow the sprite scale.
+    pub fn lasers(reach: f32) -> ApeAttackRange {
+        ApeAttackRange::lasers(reach).scaled_by(Self::SCALE / 0.8)
+    }
+
+    /// Starts at 0, the boss goes through a new phase each time it loses a share of its life.
+    pub fn phase(life: &ApeLife, tuning: &BossTuning) -> usize {
+        let lost = 1. - life.current / life.max;
+        ((lost * tuning.phases as f32) as usize).min(tuning.phases - 1)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BossAttack {
+    /// Lasers toward the player
+    Lasers,
+    /// Several lasers in a row
+    Barrage,
+    /// Runs across the arena, wounding the player on contact
+    Charge,
+}
+
+impl BossAttack {
+    pub const ALL: [Self; 3] = [Self::Lasers, Self::Barrage, Self::Charge];
+
+    /// Each phase unlocks one more attack.
+    pub fn unlocked(phase: usize) -> &'static [Self] {
+        &Self::ALL[..=phase.min(Self::ALL.len() - 1)]
+    }
+}
+
+#[derive(Component)]
+pub struct BossPattern {
+    pub cooldown: Timer,
+    /// Lasers left in the current barrage
+    pub volleys: usize,
+    /// Where the current charge ends
+    pub charge_to: Option<f32>,
+}
+
+impl BossPattern {
+    const BARRAGE_VOLLEYS: usize = 2;
+    const BARRAGE_INTERVAL: f32 = 0.3;
+
+    pub fn new(attack_interval: f32) -> Self {
+        Self {
+            cooldown: Timer::from_seconds(attack_interval, TimerMode::Once),
+            volleys: 0,
+            charge_to: None,
+        }
+    }
+}
+
+/// Tracks when the next boss is due, regular apes stay away from the arena meanwhile.
+#[derive(Resource, Debug, Default)]
+pub struct BossEncounter {
+    /// Score when the last boss was killed
+    pub kills_at_last: usize,
+    pub stage: BossStage,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum BossStage {
+    #[default]
+    Waiting,
+    /// Waiting for the remaining apes to die
+    Summoning,
+    Fighting,
+}
+
+impl BossEncounter {
+    pub fn holds_arena(&self) -> bool {
+        !matches!(self.stage, BossStage::Waiting)
+    }
+}
+
+#[derive(Component)]
+pub struct BossHud;
+
+#[derive(Component)]
+pub struct BossGauge;
+
+#[derive(Component)]
+pub struct BossText;
+
+/////////////////////////////////////// Systems ////////////////////////////////////////
+
+pub fn summon_boss(
+    score: Res<Score>,
+    tuning: Res<BossTuning>,
+    font_handle: Res<FontHandle>,
+    asset_server: Res<AssetServer>,
+    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+    mut rng: ResMut<GameRng>,
+    mut commands: Commands,
+    mut encounter: ResMut<BossEncounter>,
+    apes_q: Query<(), With<Ape>>,
+    boss_q: Query<(), With<Boss>>,
+    boss_hud_q: Query<Entity, With<BossHud>>,
+) {
+    match encounter.stage {
+        BossStage::Waiting => {
+            if score.0 - encounter.kills_at_last >= tuning.every_kills {
+                encounter.stage = BossStage::Summoning;
+            }
+        }
+        BossStage::Summoning => {
+            if apes_q.is_empty() {
+                spawn_boss(
+                    &mut commands,
+                    &asset_server,
+                    &mut texture_atlases,
+                    &mut rng,
+                    &tuning,
+                );
+                spawn_boss_hud(&mut commands, &font_handle, &tuning);
+                encounter.stage = BossStage::Fighting;
+            }
+        }
+        BossStage::Fighting => {
+            if boss_q.is_empty() {
+                for boss_hud in boss_hud_q.iter() {
+                    commands.entity(boss_hud).despawn_recursive();
+                }
+                encounter.kills_at_last = score.0;
+                encounter.stage = BossStage::Waiting;
+            }
+        }
+    }
+}
+
+pub fn move_boss(
+    time: Res<Time>,
+    tuning: Res<BossTuning>,
+    mut boss_q: Query<
+        (
+            Entity,
+            &mut Transform,
+            &mut Moving,
+            &mut BossPattern,
+            &ApeLife,
+        ),
+        With<Boss>,
+    >,
+    attacks_q: Query<&ApeAttackOwner>,
+) {
+    for (boss, mut transform, mut moving, mut pattern, life) in boss_q.iter_mut() {
+        let x = transform.translation.x;
+
+        if let Some(charge_to) = pattern.charge_to {
+            let inc = tuning.charge_speed * time.delta_seconds();
+            if (charge_to - x).abs() <= inc {
+                transform.translation.x = charge_to;
+                pattern.charge_to = None;
+            } else {
+                transform.translation.x += inc * (charge_to - x).signum();
+            }
+            continue;
+        }
+
+        // Stands still while firing
+        if attacks_q.iter().any(|owner| **owner == boss) {
+            continue;
+        }
+
+        let phase = Boss::phase(life, &tuning);
+        let inc = tuning.patrol_speed * (1. + 0.25 * phase as f32) * time.delta_seconds();
+        match &*moving {
+            Moving::Left => {
+                if x - inc > Boss::min() {
+                    transform.translation.x = x - inc;
+                } else {
+                    *moving = Moving::Right;
+                }
+            }
+            Moving::Right => {
+                if x + inc < Boss::max() {
+                    transform.translation.x = x + inc;
+                } else {
+                    *moving = Moving::Left;
+                }
+            }
+            _ => unreachable!(),
+        }
+    }
+}
+
+pub fn trigger_boss_attack(
+    time: Res<Time>,
+    tuning: Res<BossTuning>,
+    mut rng: ResMut<GameRng>,
+    mut commands: Commands,
+    player_q: Query<&Transform, (With<Player>, Without<Boss>)>,
+    mut boss_q: Query<
+        (
+            Entity,
+            &Transform,
+            &mut Sprite,
+            &mut Flank,
+            &mut Moving,
+            &mut ApeAttackSpec,
+            &mut BossPattern,
+            &ApeLife,
+        ),
+        With<Boss>,
+    >,
+    attacks_q: Query<&ApeAttackOwner>,
+) {
+    let player_x = player_q.single().translation.x;
+
+    for (boss, transform, mut sprite, mut flank, mut moving, mut attack_spec, mut pattern, life) in
+        boss_q.iter_mut()
+    {
+        let attacking = attacks_q.iter().any(|owner| **owner == boss);
+        if attacking || pattern.charge_to.is_some() {
+            continue;
+        }
+
+        pattern.cooldown.tick(time.delta());
+        if !pattern.cooldown.finished() {
+            continue;
+        }
+
+        let boss_x = transform.translation.x;
+        *flank = Flank::facing(boss_x, player_x);
+        sprite.flip_x = flank.flip_x();
+        attack_spec.flank = *flank;
+
+        let attack = if pattern.volleys > 0 {
+            pattern.volleys -= 1;
+            BossAttack::Lasers
+        } else {
+            let phase = Boss::phase(life, &tuning);
+            *BossAttack::unlocked(phase).choose(&mut *rng).unwrap()
+        };
+
+        match attack {
+            BossAttack::Lasers => spawn_ape_attack_init(&mut commands, boss, &attack_spec),
+            BossAttack::Barrage => {
+                pattern.volleys = BossPattern::BARRAGE_VOLLEYS;
+                spawn_ape_attack_init(&mut commands, boss, &attack_spec);
+            }
+            BossAttack::Charge => {
+                let (charge_to, charge_move) = match *flank {
+                    Flank::Left => (Boss::max(), Moving::Right),
+                    Flank::Right => (Boss::min(), Moving::Left),
+                };
+                pattern.charge_to = Some(charge_to);
+                *moving = charge_move;
+            }
+        }
+
+        let interval = if pattern.volleys > 0 {
+            BossPattern::BARRAGE_INTERVAL
+        } else {
+            let phase = Boss::phase(life, &tuning);
+            tuning.attack_interval * 0.75_f32.powi(phase as i32)
+        };
+        pattern
+            .cooldown
+            .set_duration(Duration::from_secs_f32(interval));
+        pattern.cooldown.reset();
+    }
+}
+
+pub fn boss_charge_collision(
+    mut commands: Commands,
+    mut ev_unit_changed: EventWriter<UnitChanged>,
+    boss_q: Query<(&Transform, &BossPattern), With<Boss>>,
+    player_q: Query<(Entity, &Transform, &UnitState, &UnitCondition), With<Player>>,
+    mut health_q: Query<&mut LifeChunks, With<LifeHud>>,
+) {
+    let (player, player_transform, player_state, player_condition) = player_q.single();
+
+    if matches!(player_condition, UnitCondition::Upgraded)
+        || matches!(
+            player_state,
+            UnitState::Dash | UnitState::Jump | UnitState::Fall | UnitState::Wound | UnitState::Die
+        )
+    {
+        return;
+    }
+
+    for (boss_transform, pattern) in boss_q.iter() {
+        let close_enough = (boss_transform.translation.x - player_transform.translation.x).abs()
+            < Boss::CONTACT_WIDTH;
+        if pattern.charge_to.is_some() && close_enough {
+            wound_player(
+                &mut commands,
+                &mut ev_unit_changed,
+                player,
+                &mut health_q.single_mut(),
+            );
+            return;
+        }
+    }
+}
+
+pub fn display_boss_hud(
+    tuning: Res<BossTuning>,
+    mut boss_q: Query<(&ApeLife, &mut Sprite), (With<Boss>, Changed<ApeLife>)>,
+    mut gauge_q: Query<&mut TessPath, With<BossGauge>>,
+    mut text_q: Query<&mut Text, With<BossText>>,
+) {
+    let Ok((life, mut sprite)) = boss_q.get_single_mut() else {
+        return;
+    };
+    let (Ok(mut gauge_path), Ok(mut text)) = (gauge_q.get_single_mut(), text_q.get_single_mut())
+    else {
+        return;
+    };
+
+    let rect_x = life.current / life.max * (500. - 3.);
+    let mut path_builder = tess::path::Path::builder();
+    let rect = shapes::Rectangle {
+        extents: Vec2::new(rect_x, 14. - 3.),
+        origin: shapes::RectangleOrigin::TopLeft,
+    };
+    rect.add_geometry(&mut path_builder);
+    *gauge_path = TessPath(path_builder.build());
+
+    // Gets redder as it goes through its phases
+    let phase = Boss::phase(life, &tuning);
+    let redness = phase as f32 / tuning.phases as f32;
+    sprite.color = Color::rgb(1., 1. - 0.5 * redness, 1. - 0.5 * redness);
+    text.sections[0].value = format!("Boss   {}  /  {}", phase + 1, tuning.phases);
+}
+
+pub fn retune_boss(
+    tuning: Res<BossTuning>,
+    mut boss_q: Query<(&mut ApeLife, &mut ApeAttackSpec), With<Boss>>,
+) {
+    if !tuning.is_changed() {
+        return;
+    }
+
+    for (mut boss_life, mut attack_spec) in boss_q.iter_mut() {
+        boss_life.rescale(tuning.life);
+        attack_spec.attack_range = Boss::lasers(tuning.laser_reach);
+        attack_spec.reach = tuning.laser_reach;
+        attack_spec.init_duration = DurationTimer::from_seconds(tuning.laser_charge);
+        attack_spec.on_duration = DurationTimer::from_seconds(tuning.laser_fire);
+    }
+}
@@ -7,19 +7,44 @@ pub fn spawn_game_state(
     asset_server: &AssetServer,
     texture_atlases: &mut Assets<TextureAtlas>,
     font_handle: &Handle<Font>,
+    ape_icon: &ApeIconHandle,
     aseprite_handles: &AsepriteHandles,
     aseprites: &Assets<Aseprite>,
+    rng: &mut GameRng,
+    player_tuning: &PlayerTuning,
+    eth_tuning: &EthTuning,
+    ape_tuning: &ApeTuning,
+    wave: &Wave,
+    wave_tuning: &WaveTuning,
 ) {
     spawn_background(commands, asset_server);
     spawn_platform(commands, asset_server);
 
-    spawn_player(commands, aseprite_handles, aseprites);
-    spawn_life_hud(commands, asset_server);
+    spawn_player(
+        commands,
+        aseprite_handles,
+        aseprites,
+        player_tuning,
+        eth_tuning,
+    );
+    spawn_life_hud(commands, asset_server, player_tuning.life_chunks);
 
     spawn_eth_hud(commands, asset_server);
 
-    spawn_ape(commands, asset_server, texture_atlases, Flank::Left);
-    spawn_ape(commands, asset_server, texture_atlases, Flank::Right);
+    for slot in wave.slots(wave_tuning, ape_tuning.slots.len()) {
+        spawn_ape(
+            commands,
+            asset_server,
+            texture_atlases,
+            font_handle,
+            ape_icon,
+            rng,
+            ape_tuning,
+            wave,
+            wave_tuning,
+            slot,
+        );
+    }
     spawn_dead_apes_hud(commands, asset_server, font_handle);
 }
 
@@ -146,6 +171,60 @@ pub fn spawn_gameover_screen(
                 transform: Transform::from_xyz(0., -380., 0.),
                 ..default()
             });
+
+            parent
+                .spawn(Text2dBundle {
+                    text: Text::from_section(
+                        "Seed   0",
+                        TextStyle {
+                            font: font_handle.clone(),
+                            font_size: 20.0,
+                            color: Color::GRAY,
+                        },
+                    )
+                    .with_alignment(alignment),
+                    visibility: Visibility::Inherited,
+                    transform: Transform::from_xyz(0., -440., 0.),
+                    ..default()
+                })
+                .insert(SeedText);
+
+            spawn_high_scores(parent, font_handle);
+        });
+}
+
+pub fn spawn_title_screen(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    font_handle: &Handle<Font>,
+) {
+    commands
+        .spawn(Text2dBundle {
+            text: Text::from_section(
+                "Press   << attack >>   to   start   a   new   fight",
+                TextStyle {
+                    font: font_handle.clone(),
+                    font_size: 30.0,
+                    color: Color::WHITE,
+                },
+            )
+            .with_alignment(TextAlignment::Center),
+            visibility: Visibility::Hidden,
+            transform: Transform::from_xyz(0., -230., 10.),
+            ..default()
+        })
+        .insert(TitleElements)
+        .with_children(|parent| {
+            parent.spawn(SpriteBundle {
+                texture: asset_server.load("cover.png"),
+                transform: Transform {
+                    scale: Vec3::splat(0.8),
+                    translation: Vec3::new(0., 260., 0.),
+                    ..default()
+                },
+                visibility: Visibility::Inherited,
+                ..default()
+            });
         });
 }
 
@@ -158,7 +237,9 @@ pub struct AsepriteHandles(HashMap<&'static str, Handle<Aseprite>>);
 pub enum AppState {
     #[default]
     Loading,
+    Title,
     InGame,
+    Paused,
     GameOver,
 }
 
@@ -168,6 +249,9 @@ pub struct Scenary;
 #[derive(Component)]
 pub struct GameoverElements;
 
+#[derive(Component)]
+pub struct TitleElements;
+
 #[derive(Component)]
 pub struct Animation {
     pub timer: Timer,
@@ -208,17 +292,12 @@ impl DurationTimer {
         self.0.finished()
     }
 
-    pub fn tick(&mut self, delta: Duration) {
-        self.0.tick(delta);
+    pub fn duration(&self) -> Duration {
+        self.0.duration()
     }
-}
 
-#[derive(Component, Deref, DerefMut)]
-pub struct TriggerTimer(Timer);
-
-impl Default for TriggerTimer {
-    fn default() -> Self {
-        Self(Timer::from_seconds(3., TimerMode::Repeating))
+    pub fn tick(&mut self, delta: Duration) {
+        self.0.tick(delta);
     }
 }
 
@@ -231,23 +310,163 @@ pub struct ScoreText;
 #[derive(Component)]
 pub struct ScoreTextIcon;
 
+#[derive(Component)]
+pub struct SeedText;
+
+/// Source of all gameplay randomness, so that a run can be replayed from its seed.
+#[derive(Resource)]
+pub struct GameRng {
+    seed: u64,
+    rng: StdRng,
+}
+
+impl Default for GameRng {
+    fn default() -> Self {
+        Self::from_seed(rand::random())
+    }
+}
+
+impl GameRng {
+    pub fn from_seed(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Prepares the next run with a seed drawn from the current one, so that a whole session
+    /// can be replayed from its first seed.
+    pub fn reseed(&mut self) {
+        *self = Self::from_seed(self.rng.gen());
+    }
+}
+
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
+
 #[derive(Resource, Deref, DerefMut, Debug, Clone)]
 pub struct FontHandle(Handle<Font>);
 
+/// The handles loaded once at setup that the game state spawners need.
+#[derive(SystemParam)]
+pub struct GameStateHandles<'w> {
+    pub font: Res<'w, FontHandle>,
+    pub ape_icon: Res<'w, ApeIconHandle>,
+}
+
 /////////////////////////////////////// Systems ////////////////////////////////////////
 
+pub fn load_assets(mut aseprite_handles: ResMut<AsepriteHandles>, asset_server: Res<AssetServer>) {
+    for asprite_path in [sprites::Paladin::PATH, sprites::Crusader::PATH] {
+        let aseprite = asset_server.load(asprite_path);
+        aseprite_handles.insert(asprite_path, aseprite);
+    }
+}
+
+pub fn check_assets(
+    aseprite_handles: ResMut<AsepriteHandles>,
+    tuning_h: Res<TuningHandle>,
+    asset_server: Res<AssetServer>,
+    mut state: ResMut<NextState<AppState>>,
+) {
+    aseprite_handles
+        .values()
+        .map(|handle| handle.id().untyped())
+        .chain([tuning_h.0.id().untyped()])
+        .all(|id| matches!(asset_server.get_load_state(id), Some(LoadState::Loaded)))
+        .then(|| state.set(AppState::InGame));
+}
+
+pub fn setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+    aseprite_handles: Res<AsepriteHandles>,
+    aseprites: Res<Assets<Aseprite>>,
+    mut rng: ResMut<GameRng>,
+    player_tuning: Res<PlayerTuning>,
+    eth_tuning: Res<EthTuning>,
+    ape_tuning: Res<ApeTuning>,
+    wave: Res<Wave>,
+    wave_tuning: Res<WaveTuning>,
+) {
+    init_eth(
+        &mut commands,
+        &asset_server,
+        &mut texture_atlases,
+        &eth_tuning,
+    );
+
+    let font_handle = spawn_font(&mut commands, &asset_server);
+    let ape_icon = init_ape_icon(&mut commands, &asset_server);
+    spawn_gameover_screen(&mut commands, &asset_server, &font_handle, &ape_icon);
+    spawn_title_screen(&mut commands, &asset_server, &font_handle);
+    spawn_pause_menu(&mut commands, &font_handle);
+    spawn_wave_banner(&mut commands, &font_handle);
+    spawn_kill_feed(&mut commands);
+
+    spawn_camera(&mut commands);
+
+    spawn_game_state(
+        &mut commands,
+        &asset_server,
+        &mut texture_atlases,
+        &font_handle,
+        &ape_icon,
+        &aseprite_handles,
+        &aseprites,
+        &mut rng,
+        &player_tuning,
+        &eth_tuning,
+        &ape_tuning,
+        &wave,
+        &wave_tuning,
+    );
+}
+
 pub fn despawn_game_state(
     mut commands: Commands,
     entities_query: Query<
         Entity,
         Or<(
             With<Player>,
-            With<LifeHud>,
             With<Ape>,
-            With<DeadApesHud>,
+            With<Boss>,
+            With<Minion>,
+            With<ApeDeath>,
+            With<DeadIcon>,
+            With<LandingDust>,
+            With<ApeAttackOwner>,
+            With<Shard>,
             With<Eth>,
-            With<EthHud>,
+            With<PowerUp>,
             With<Scenary>,
+            Or<(
+                With<LifeHud>,
+                With<BossHud>,
+                With<DeadApesHud>,
+                With<EthHud>,
+                With<KillFeedEntry>,
+            )>,
         )>,
     >,
 ) {
@@ -258,63 +477,88 @@ pub fn despawn_game_state(
 
 pub fn respawn_game_state(
     mut commands: Commands,
-    font_handle: Res<FontHandle>,
+    handles: GameStateHandles,
     asset_server: Res<AssetServer>,
     aseprite_handles: Res<AsepriteHandles>,
     aseprites: Res<Assets<Aseprite>>,
     mut texture_atlases: ResMut<Assets<TextureAtlas>>,
     mut gameover_elements_q: Query<&mut Visibility, With<GameoverElements>>,
     mut score: ResMut<Score>,
+    mut run_stats: ResMut<RunStats>,
+    mut wave: ResMut<Wave>,
+    mut boss_encounter: ResMut<BossEncounter>,
+    mut rng: ResMut<GameRng>,
+    player_tuning: Res<PlayerTuning>,
+    eth_tuning: Res<EthTuning>,
+    ape_tuning: Res<ApeTuning>,
+    wave_tuning: Res<WaveTuning>,
 ) {
     *gameover_elements_q.single_mut() = Visibility::Hidden;
 
     score.0 = 0;
+    *run_stats = RunStats::default();
+    *wave = Wave::default();
+    *boss_encounter = BossEncounter::default();
+    rng.reseed();
 
     spawn_game_state(
         &mut commands,
         &asset_server,
         &mut texture_atlases,
-        &font_handle,
+        &handles.font,
+        &handles.ape_icon,
         &aseprite_handles,
         &aseprites,
+        &mut rng,
+        &player_tuning,
+        &eth_tuning,
+        &ape_tuning,
+        &wave,
+        &wave_tuning,
     );
 }
 
 pub fn gameover_screen(
     input_kind: Res<InputKind>,
     keys: Res<Input<KeyCode>>,
-    gamepads: Res<Gamepads>,
     buttons: Res<Input<GamepadButton>>,
     score: Res<Score>,
+    rng: Res<GameRng>,
     mut app_state: ResMut<NextState<AppState>>,
     mut gameover_elements_q: Query<&mut Visibility, With<GameoverElements>>,
     mut text_q: Query<(&mut Text, &TextLayoutInfo), With<ScoreText>>,
     mut icon_q: Query<&mut Transform, With<ScoreTextIcon>>,
+    mut seed_text_q: Query<&mut Text, (With<SeedText>, Without<ScoreText>)>,
 ) {
     let (mut text, text_size) = text_q.single_mut();
     text.sections[0].value = format!("You   managed   to   kill   [ {} ]", score.0);
     let icon_offset = text_size.logical_size.x / 2. + 30.;
     icon_q.single_mut().translation.x = icon_offset;
 
+    seed_text_q.single_mut().sections[0].value = format!("Seed   {}", rng.seed());
+
     *gameover_elements_q.single_mut() = Visibility::Visible;
 
-    match &*input_kind {
-        InputKind::Keyboard => {
-            if keys.just_released(PlayerInput::ATTACK) {
-                app_state.set(AppState::InGame);
-            }
-        }
-        InputKind::Gamepad => {
-            let gamepad = Gamepad { id: 0 };
-            if !gamepads.contains(gamepad) {
-                return;
-            }
-            if buttons.just_released(GamepadButton {
-                gamepad,
-                button_type: GamepadButtonType::West,
-            }) {
-                app_state.set(AppState::InGame);
-            }
-        }
+    if let Some(MenuAction::Confirm) = MenuAction::detect(&input_kind, &keys, &buttons) {
+        app_state.set(AppState::InGame);
+    }
+}
+
+pub fn show_title_screen(mut title_elements_q: Query<&mut Visibility, With<TitleElements>>) {
+    *title_elements_q.single_mut() = Visibility::Visible;
+}
+
+pub fn hide_title_screen(mut title_elements_q: Query<&mut Visibility, With<TitleElements>>) {
+    *title_elements_q.single_mut() = Visibility::Hidden;
+}
+
+pub fn title_screen(
+    input_kind: Res<InputKind>,
+    keys: Res<Input<KeyCode>>,
+    buttons: Res<Input<GamepadButton>>,
+    mut app_state: ResMut<NextState<AppState>>,
+) {
+    if let Some(MenuAction::Confirm) = MenuAction::detect(&input_kind, &keys, &buttons) {
+        app_state.set(AppState::InGame);
     }
 }
@@ -1,11 +1,33 @@
 use crate::prelude::*;
 
+//////////////////////////////////////// Plugin ////////////////////////////////////////
+
+pub struct EthPlugin;
+
+impl Plugin for EthPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                make_eth,
+                animate_eth,
+                player_collects_eth,
+                player_eth_gauge,
+                decay_player_eth,
+                retune_eth,
+            )
+                .run_if(in_state(AppState::InGame)),
+        );
+    }
+}
+
 /////////////////////////////////////// Spawners ///////////////////////////////////////
 
 pub fn init_eth(
     commands: &mut Commands,
     asset_server: &AssetServer,
     texture_atlases: &mut Assets<TextureAtlas>,
+    tuning: &EthTuning,
 ) {
     let eth_image = asset_server.load("eth.png");
     let eth_atlas = TextureAtlas::from_grid(eth_image, Vec2::new(50.0, 50.0), 1, 11, None, None);
@@ -13,10 +35,15 @@ pub fn init_eth(
 
     commands.insert_resource(EthHandle(eth_atlas_h));
 
-    commands.insert_resource(EthPicked(Instant::now()))
+    commands.insert_resource(EthPicked::from_seconds(tuning.respawn_delay))
 }
 
-pub fn spawn_eth(commands: &mut Commands, position: Vec3, eth_handle: &EthHandle) {
+pub fn spawn_eth(
+    commands: &mut Commands,
+    position: Vec3,
+    quantity: f32,
+    eth_handle: &EthHandle,
+) -> Entity {
     commands
         .spawn(SpriteSheetBundle {
             texture_atlas: eth_handle.0.clone(),
@@ -31,7 +58,8 @@ pub fn spawn_eth(commands: &mut Commands, position: Vec3, eth_handle: &EthHandle
             timer: Timer::from_seconds(0.12, TimerMode::Repeating),
             count: None,
         })
-        .insert(Eth::default());
+        .insert(Eth { quantity })
+        .id()
 }
 
 pub fn spawn_eth_hud(commands: &mut Commands, asset_server: &AssetServer) {
@@ -103,28 +131,17 @@ pub struct Eth {
     pub quantity: f32,
 }
 
-impl Default for Eth {
-    fn default() -> Self {
-        Self { quantity: 10. }
-    }
-}
-
 #[derive(Debug, Component)]
 pub struct EthOwned {
     pub current: f32,
     pub max: f32,
 }
 
-impl Default for EthOwned {
-    fn default() -> Self {
-        Self {
-            current: 0.,
-            max: 30.,
-        }
+impl EthOwned {
+    pub fn new(max: f32) -> Self {
+        Self { current: 0., max }
     }
-}
 
-impl EthOwned {
     pub fn add(&mut self, eth: &Eth) {
         self.current = self.max.min(self.current + eth.quantity);
     }
@@ -148,21 +165,33 @@ pub struct EthHud;
 #[derive(Component)]
 pub struct EthGauge;
 
-#[derive(Resource, Deref)]
-pub struct EthPicked(pub Instant);
+/// Delay before a new ETH shows up once the previous one has been picked.
+#[derive(Resource, Deref, DerefMut)]
+pub struct EthPicked(pub Timer);
+
+impl EthPicked {
+    pub fn from_seconds(duration: f32) -> Self {
+        Self(Timer::from_seconds(duration, TimerMode::Once))
+    }
+}
 
 /////////////////////////////////////// Systems ////////////////////////////////////////
 
 pub fn make_eth(
+    time: Res<Time>,
+    tuning: Res<EthTuning>,
     eth_handle: Res<EthHandle>,
-    picked_eth_at: Res<EthPicked>,
+    mut eth_picked: ResMut<EthPicked>,
+    mut rng: ResMut<GameRng>,
     mut commands: Commands,
     player_q: Query<&Transform, With<Player>>,
     eth_q: Query<Entity, With<Eth>>,
 ) {
+    eth_picked.tick(time.delta());
+
     let eth_count = eth_q.iter().count();
 
-    if eth_count == 0 && picked_eth_at.elapsed() > Duration::from_secs(3) {
+    if eth_count == 0 && eth_picked.finished() {
         let player_x = player_q.single().translation.x;
 
         let (a, b) = if player_x < -0.3 * (GLOBAL_WIDTH / 2.) {
@@ -174,19 +203,25 @@ pub fn make_eth(
         };
 
         let beta = Beta::new(a, b).unwrap();
-        let v = beta.sample(&mut rand::thread_rng());
+        let v = beta.sample(&mut *rng);
         let x = v * (GLOBAL_WIDTH - 100.) - (GLOBAL_WIDTH - 100.) / 2.;
 
-        spawn_eth(&mut commands, Vec3::new(x, -222., 20.), &eth_handle);
+        spawn_eth(
+            &mut commands,
+            Vec3::new(x, -222., 20.),
+            tuning.quantity,
+            &eth_handle,
+        );
     }
 }
 
 pub fn player_collects_eth(
-    mut picked_eth_at: ResMut<EthPicked>,
+    mut eth_picked: ResMut<EthPicked>,
+    mut run_stats: ResMut<RunStats>,
     mut commands: Commands,
     mut ev_unit_changed: EventWriter<UnitChanged>,
     mut player_q: Query<(Entity, &Transform, &mut EthOwned), With<Player>>,
-    eth_q: Query<(Entity, &Eth, &Transform)>,
+    eth_q: Query<(Entity, &Eth, &Transform), Without<Falling>>,
 ) {
     let (player, player_transform, mut player_eth) = player_q.single_mut();
     let player_x = player_transform.translation.x;
@@ -196,8 +231,9 @@ pub fn player_collects_eth(
         let eth_x = eth_transform.translation.x;
         if (player_x - eth_x).abs() < 30. && player_y < -100. {
             player_eth.add(eth);
+            run_stats.eth_collected += eth.quantity;
             commands.entity(eth_id).despawn();
-            picked_eth_at.0 = Instant::now();
+            eth_picked.reset();
 
             if player_eth.is_full() {
                 ev_unit_changed
@@ -209,13 +245,14 @@ pub fn player_collects_eth(
 
 pub fn decay_player_eth(
     time: Res<Time>,
+    tuning: Res<EthTuning>,
     mut ev_unit_changed: EventWriter<UnitChanged>,
     mut player_q: Query<(Entity, &mut EthOwned, &UnitCondition), With<Player>>,
 ) {
     let (player, mut player_eth, player_condition) = player_q.single_mut();
 
     if let UnitCondition::Upgraded = player_condition {
-        player_eth.remove(2. * time.delta_seconds());
+        player_eth.remove(tuning.decay * time.delta_seconds());
         if player_eth.is_empty() {
             ev_unit_changed.send(UnitChanged::entity(player).new_condition(UnitCondition::Normal));
         }
@@ -265,3 +302,19 @@ pub fn animate_eth(
         }
     }
 }
+
+pub fn retune_eth(
+    tuning: Res<EthTuning>,
+    mut eth_picked: ResMut<EthPicked>,
+    mut player_q: Query<&mut EthOwned, With<Player>>,
+) {
+    if !tuning.is_changed() {
+        return;
+    }
+
+    eth_picked.set_duration(Duration::from_secs_f32(tuning.respawn_delay));
+    for mut player_eth in player_q.iter_mut() {
+        player_eth.max = tuning.max;
+        player_eth.current = player_eth.current.min(player_eth.max);
+    }
+}
@@ -0,0 +1,31 @@
+use crate::prelude::*;
+
+//////////////////////////////////////// Plugin ////////////////////////////////////////
+
+/// Replaces `DefaultPlugins`, `ShapePlugin` and `AsepritePlugin` when no window nor GPU is
+/// available.
+///
+/// Images and aseprite files are still decoded on the CPU so that atlases and animation
+/// metadata exist, but nothing is ever rendered. Fonts, texts and lyon shapes are spawned as
+/// plain components that no system consumes.
+pub struct HeadlessPlugin;
+
+impl Plugin for HeadlessPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin {
+                watch_for_changes_override: Some(false),
+                ..default()
+            },
+            TransformPlugin,
+            HierarchyPlugin,
+            bevy::input::InputPlugin,
+            ImagePlugin::default_nearest(),
+            AsepritePlugin,
+        ))
+        .init_asset::<TextureAtlas>()
+        .init_asset::<Font>()
+        .init_asset_loader::<FontLoader>();
+    }
+}
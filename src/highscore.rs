@@ -0,0 +1,263 @@
+use std::path::PathBuf;
+
+use crate::prelude::*;
+
+//////////////////////////////////////// Plugin ////////////////////////////////////////
+
+/// Keeps the best runs across sessions and shows them on the gameover screen.
+///
+/// The table is read from the user's data directory unless a [`HighScores`] resource has
+/// already been inserted, which is how headless runs keep an in-memory table only.
+pub struct HighScorePlugin;
+
+impl Plugin for HighScorePlugin {
+    fn build(&self, app: &mut App) {
+        if !app.world.contains_resource::<HighScores>() {
+            app.insert_resource(HighScores::load());
+        }
+
+        app.init_resource::<RunStats>()
+            .add_systems(Update, track_survival.run_if(in_state(AppState::InGame)))
+            .add_systems(
+                OnEnter(AppState::GameOver),
+                (record_high_score, show_high_scores).chain(),
+            );
+    }
+}
+
+/////////////////////////////////////// Spawners ///////////////////////////////////////
+
+pub fn spawn_high_scores(parent: &mut ChildBuilder, font_handle: &Handle<Font>) {
+    let style = |font_size, color| TextStyle {
+        font: font_handle.clone(),
+        font_size,
+        color,
+    };
+
+    parent
+        .spawn(Text2dBundle {
+            text: Text::from_section("High   scores", style(24., Color::WHITE)),
+            text_anchor: Anchor::CenterLeft,
+            visibility: Visibility::Inherited,
+            transform: Transform::from_xyz(260., -200., 0.),
+            ..default()
+        })
+        .insert(HighScoresTitle);
+
+    for rank in 0..HighScores::MAX_ENTRIES {
+        parent
+            .spawn(Text2dBundle {
+                text: Text::from_section("", style(18., Color::WHITE)),
+                text_anchor: Anchor::CenterLeft,
+                visibility: Visibility::Inherited,
+                transform: Transform::from_xyz(260., -235. - 25. * rank as f32, 0.),
+                ..default()
+            })
+            .insert(HighScoreRow(rank));
+    }
+}
+
+////////////////////////////////////// Components //////////////////////////////////////
+
+#[derive(Component)]
+pub struct HighScoresTitle;
+
+#[derive(Component)]
+pub struct HighScoreRow(pub usize);
+
+/// What happened during the current run, besides the kills counted by [`Score`].
+#[derive(Resource, Debug, Default)]
+pub struct RunStats {
+    pub survival: Duration,
+    pub eth_collected: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HighScore {
+    pub kills: usize,
+    pub survival_secs: f32,
+    pub eth_collected: f32,
+    /// Day of the run, formatted as `YYYY-MM-DD`
+    pub date: String,
+}
+
+impl HighScore {
+    pub fn row(&self, rank: usize) -> String {
+        let secs = self.survival_secs as u32;
+        format!(
+            "{}.   {}   kills   {}:{:02}   {}   ETH   {}",
+            rank + 1,
+            self.kills,
+            secs / 60,
+            secs % 60,
+            self.eth_collected.round(),
+            self.date
+        )
+    }
+
+    /// More kills first, then the longest survival.
+    fn beats(&self, other: &Self) -> bool {
+        (self.kills, self.survival_secs) > (other.kills, other.survival_secs)
+    }
+}
+
+/// The best runs, sorted from the best one.
+#[derive(Resource, Debug, Default)]
+pub struct HighScores {
+    pub entries: Vec<HighScore>,
+    /// Rank of the last recorded run, if it made it to the table
+    pub latest: Option<usize>,
+    /// Where the table is saved, in-memory only when `None`
+    pub path: Option<PathBuf>,
+}
+
+impl HighScores {
+    pub const MAX_ENTRIES: usize = 5;
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn default_path() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join("angry-apes").join("highscores.ron"))
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn default_path() -> Option<PathBuf> {
+        None
+    }
+
+    /// Reads the saved table, a missing or unreadable file starts a new one.
+    pub fn load() -> Self {
+        let path = Self::default_path();
+        let entries = path
+            .as_ref()
+            .and_then(|path| match std::fs::read(path) {
+                Ok(bytes) => ron::de::from_bytes(&bytes)
+                    .map_err(|e| warn!("Ignoring malformed {}: {e}", path.display()))
+                    .ok(),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+                Err(e) => {
+                    warn!("Could not read {}: {e}", path.display());
+                    None
+                }
+            })
+            .unwrap_or_default();
+
+        Self {
+            entries,
+            latest: None,
+            path,
+        }
+    }
+
+    pub fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+
+        let written = path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| {
+                let ron = ron::ser::to_string_pretty(&self.entries, default())
+                    .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+                std::fs::write(path, ron)
+            });
+
+        if let Err(e) = written {
+            error!("Could not write {}: {e}", path.display());
+        }
+    }
+
+    /// Inserts the run at its rank, returns `None` when it is not good enough for the table.
+    pub fn insert(&mut self, high_score: HighScore) -> Option<usize> {
+        let rank = self
+            .entries
+            .iter()
+            .position(|entry| high_score.beats(entry))
+            .unwrap_or(self.entries.len());
+
+        if rank >= Self::MAX_ENTRIES {
+            return None;
+        }
+
+        self.entries.insert(rank, high_score);
+        self.entries.truncate(Self::MAX_ENTRIES);
+        Some(rank)
+    }
+}
+
+/// Today's date as `YYYY-MM-DD` (UTC).
+fn today() -> String {
+    #[cfg(not(target_arch = "wasm32"))]
+    let millis = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis() as i64);
+    #[cfg(target_arch = "wasm32")]
+    let millis = js_sys::Date::now() as i64;
+
+    civil_date(millis)
+}
+
+/// The `YYYY-MM-DD` date (UTC) of a time given in milliseconds since the Unix epoch.
+pub fn civil_date(millis: i64) -> String {
+    // Civil date from days since epoch, see http://howardhinnant.github.io/date_algorithms.html
+    let z = millis.div_euclid(86_400_000) + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/////////////////////////////////////// Systems ////////////////////////////////////////
+
+pub fn track_survival(time: Res<Time>, mut run_stats: ResMut<RunStats>) {
+    run_stats.survival += time.delta();
+}
+
+pub fn record_high_score(
+    score: Res<Score>,
+    run_stats: Res<RunStats>,
+    mut high_scores: ResMut<HighScores>,
+) {
+    high_scores.latest = high_scores.insert(HighScore {
+        kills: score.0,
+        survival_secs: run_stats.survival.as_secs_f32(),
+        eth_collected: run_stats.eth_collected,
+        date: today(),
+    });
+
+    if high_scores.latest.is_some() {
+        high_scores.save();
+    }
+}
+
+pub fn show_high_scores(
+    high_scores: Res<HighScores>,
+    mut title_q: Query<&mut Text, With<HighScoresTitle>>,
+    mut rows_q: Query<(&mut Text, &HighScoreRow), Without<HighScoresTitle>>,
+) {
+    let title = &mut title_q.single_mut().sections[0];
+    (title.value, title.style.color) = match high_scores.latest {
+        Some(0) => ("New   record  !".to_string(), Color::GOLD),
+        _ => ("High   scores".to_string(), Color::WHITE),
+    };
+
+    for (mut text, &HighScoreRow(rank)) in rows_q.iter_mut() {
+        let section = &mut text.sections[0];
+        section.value = high_scores
+            .entries
+            .get(rank)
+            .map(|entry| entry.row(rank))
+            .unwrap_or_default();
+        section.style.color = if high_scores.latest == Some(rank) {
+            Color::GOLD
+        } else {
+            Color::WHITE
+        };
+    }
+}
@@ -1,19 +1,36 @@
 use crate::prelude::*;
 
+//////////////////////////////////////// Plugin ////////////////////////////////////////
+
+pub struct InputPlugin;
+
+impl Plugin for InputPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<InputKind>()
+            .init_resource::<PlayerActions>()
+            .add_systems(
+                Update,
+                (
+                    gamepad_connection_events.before(read_player_actions),
+                    read_player_actions
+                        .before(handle_input)
+                        .run_if(not(resource_exists::<Playback>())),
+                    handle_input,
+                )
+                    .run_if(in_state(AppState::InGame)),
+            );
+    }
+}
+
 ////////////////////////////////////// Components //////////////////////////////////////
 
-#[derive(Resource)]
+#[derive(Resource, Default)]
 pub enum InputKind {
+    #[default]
     Keyboard,
     Gamepad,
 }
 
-impl Default for InputKind {
-    fn default() -> Self {
-        Self::Keyboard
-    }
-}
-
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Component)]
 pub enum Moving {
     Left,
@@ -22,7 +39,7 @@ pub enum Moving {
     Right,
 }
 
-#[derive(Debug, Clone, Component, Deref)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Component, Deref)]
 pub struct Movements(pub HashSet<Moving>);
 
 impl Movements {
@@ -134,6 +151,91 @@ impl Movements {
     }
 }
 
+/// What the player asked for during the current frame, whatever the input device.
+///
+/// It is the only input read by `handle_input`, so that it can be recorded and replayed.
+#[derive(Resource, Debug, Clone, Default, PartialEq, Eq)]
+pub struct PlayerActions {
+    pub movements: Movements,
+    pub orientation: Option<Orientation>,
+    pub jump: bool,
+    pub dash: bool,
+    pub attack: bool,
+    pub direction_pressed: bool,
+    pub direction_released: bool,
+}
+
+impl PlayerActions {
+    const MOVINGS: [Moving; 4] = [Moving::Left, Moving::Up, Moving::Down, Moving::Right];
+
+    pub fn from_input(input: &PlayerInput<'_>) -> Self {
+        Self {
+            movements: Movements::from_input(input),
+            orientation: Orientation::from_input(input),
+            jump: input.jump_detected(),
+            dash: input.dash_detected(),
+            attack: input.attack_detected(),
+            direction_pressed: input.direction_pressed(),
+            direction_released: input.direction_just_released(),
+        }
+    }
+
+    /// Packs the actions in 11 bits: movements, orientation then the detected actions.
+    pub fn to_bits(&self) -> u16 {
+        let mut bits = 0;
+        for (i, moving) in Self::MOVINGS.iter().enumerate() {
+            if self.movements.contains(moving) {
+                bits |= 1 << i;
+            }
+        }
+        bits |= match self.orientation {
+            None => 0,
+            Some(Orientation::Left) => 1 << 4,
+            Some(Orientation::Right) => 2 << 4,
+        };
+        for (i, flag) in [
+            self.jump,
+            self.dash,
+            self.attack,
+            self.direction_pressed,
+            self.direction_released,
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            if flag {
+                bits |= 1 << (6 + i);
+            }
+        }
+        bits
+    }
+
+    pub fn from_bits(bits: u16) -> Self {
+        let movements = Self::MOVINGS
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| bits & (1 << i) != 0)
+            .map(|(_, moving)| moving.clone())
+            .collect();
+        let orientation = match (bits >> 4) & 0b11 {
+            1 => Some(Orientation::Left),
+            2 => Some(Orientation::Right),
+            _ => None,
+        };
+        let flag = |i: u16| bits & (1 << (6 + i)) != 0;
+
+        Self {
+            movements: Movements(movements),
+            orientation,
+            jump: flag(0),
+            dash: flag(1),
+            attack: flag(2),
+            direction_pressed: flag(3),
+            direction_released: flag(4),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
 pub enum Orientation {
     Left,
@@ -358,14 +460,65 @@ impl<'a> PlayerInput<'a> {
     }
 }
 
-pub fn handle_input(
+/// Actions used to navigate the title, pause and gameover screens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuAction {
+    Up,
+    Down,
+    Confirm,
+    Pause,
+}
+
+impl MenuAction {
+    pub const PAUSE: KeyCode = KeyCode::Escape;
+
+    pub fn detect(
+        input_kind: &InputKind,
+        keys: &Input<KeyCode>,
+        buttons: &Input<GamepadButton>,
+    ) -> Option<Self> {
+        match input_kind {
+            InputKind::Keyboard => {
+                if keys.just_pressed(Self::PAUSE) {
+                    Some(Self::Pause)
+                } else if keys.just_released(PlayerInput::ATTACK) {
+                    Some(Self::Confirm)
+                } else if keys.just_pressed(PlayerInput::UP) {
+                    Some(Self::Up)
+                } else if keys.just_pressed(PlayerInput::DOWN) {
+                    Some(Self::Down)
+                } else {
+                    None
+                }
+            }
+            InputKind::Gamepad => {
+                let gamepad = Gamepad { id: 0 };
+                let button = |button_type| GamepadButton {
+                    gamepad,
+                    button_type,
+                };
+                if buttons.just_pressed(button(GamepadButtonType::Start)) {
+                    Some(Self::Pause)
+                } else if buttons.just_released(button(GamepadButtonType::West)) {
+                    Some(Self::Confirm)
+                } else if buttons.just_pressed(button(GamepadButtonType::DPadUp)) {
+                    Some(Self::Up)
+                } else if buttons.just_pressed(button(GamepadButtonType::DPadDown)) {
+                    Some(Self::Down)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+}
+
+pub fn read_player_actions(
     input_kind: Res<InputKind>,
     keys: Res<Input<KeyCode>>,
     buttons: Res<Input<GamepadButton>>,
     axes: Res<Axis<GamepadAxis>>,
-    mut commands: Commands,
-    mut ev_unit_changed: EventWriter<UnitChanged>,
-    player_q: Query<(Entity, &UnitState, &Orientation, &DashCooldown), With<Player>>,
+    mut actions: ResMut<PlayerActions>,
 ) {
     let input = match *input_kind {
         InputKind::Keyboard => PlayerInput::Keyboard { keys: &keys },
@@ -376,11 +529,21 @@ pub fn handle_input(
         },
     };
 
+    *actions = PlayerActions::from_input(&input);
+}
+
+#[allow(clippy::needless_return)]
+pub fn handle_input(
+    actions: Res<PlayerActions>,
+    mut commands: Commands,
+    mut ev_unit_changed: EventWriter<UnitChanged>,
+    player_q: Query<(Entity, &UnitState, &Orientation, &DashCooldown), With<Player>>,
+) {
     let (player, unit_state, &orientation, cooldown) = player_q.single();
 
-    let new_orientation = Orientation::from_input(&input);
+    let new_orientation = actions.orientation;
 
-    if input.jump_detected() {
+    if actions.jump {
         match *unit_state {
             UnitState::Stand | UnitState::Move => (),
             _ => return,
@@ -391,7 +554,7 @@ pub fn handle_input(
                 .new_state(UnitState::Jump)
                 .new_orientation(new_orientation),
         );
-    } else if input.dash_detected() {
+    } else if actions.dash {
         match *unit_state {
             UnitState::Stand | UnitState::Move | UnitState::Jump if cooldown.finished() => (),
             _ => return,
@@ -406,13 +569,13 @@ pub fn handle_input(
                 .new_state(UnitState::Dash)
                 .new_orientation(new_orientation),
         );
-    } else if input.direction_pressed() && !input.attack_detected() {
+    } else if actions.direction_pressed && !actions.attack {
         match *unit_state {
             UnitState::Attack | UnitState::Wound | UnitState::Die | UnitState::Dash => {
                 return;
             }
             UnitState::Move | UnitState::Jump | UnitState::Fall => {
-                let movements = Movements::from_input(&input);
+                let movements = actions.movements.clone();
                 let new_orientation = Orientation::from_movements(&movements);
 
                 commands.entity(player).insert(movements);
@@ -420,9 +583,7 @@ pub fn handle_input(
                 ev_unit_changed.send(UnitChanged::entity(player).new_orientation(new_orientation));
             }
             UnitState::Stand => {
-                commands
-                    .entity(player)
-                    .insert(Movements::from_input(&input));
+                commands.entity(player).insert(actions.movements.clone());
 
                 ev_unit_changed.send(
                     UnitChanged::entity(player)
@@ -431,7 +592,7 @@ pub fn handle_input(
                 );
             }
         }
-    } else if input.direction_just_released() && !input.attack_detected() {
+    } else if actions.direction_released && !actions.attack {
         match *unit_state {
             UnitState::Move => (),
             _ => return,
@@ -442,7 +603,7 @@ pub fn handle_input(
                 .new_state(UnitState::Stand)
                 .new_orientation(new_orientation),
         );
-    } else if input.attack_detected() {
+    } else if actions.attack {
         match *unit_state {
             UnitState::Attack | UnitState::Wound => return,
             _ => (),
@@ -0,0 +1,162 @@
+#![allow(clippy::type_complexity)]
+#![allow(clippy::too_many_arguments)]
+#![allow(clippy::single_component_path_imports)]
+
+mod ape;
+mod attacks;
+mod boss;
+mod common;
+mod eth;
+mod headless;
+mod highscore;
+mod inputs;
+mod loot;
+mod minion;
+mod nft;
+mod pause;
+mod player;
+mod projectile;
+mod replay;
+mod tuning;
+mod wave;
+
+pub mod prelude {
+    pub use std::collections::{HashMap, HashSet};
+
+    #[cfg(target_arch = "wasm32")]
+    pub use instant::{Duration, Instant};
+    #[cfg(not(target_arch = "wasm32"))]
+    pub use std::time::{Duration, Instant};
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[cfg(debug_assertions)]
+    #[allow(unused_imports)]
+    use bevy_dylib;
+
+    pub use bevy::asset::io::Reader;
+    pub use bevy::asset::{AssetLoader, AsyncReadExt, LoadContext, LoadState};
+    pub use bevy::ecs::event::Events;
+    pub use bevy::ecs::system::SystemParam;
+    pub use bevy::input::gamepad::{
+        Gamepad, GamepadAxisType, GamepadButton, GamepadConnection, GamepadConnectionEvent,
+        GamepadEvent,
+    };
+    pub use bevy::prelude::*;
+    pub use bevy::render::camera::OrthographicProjection;
+    pub use bevy::render::camera::ScalingMode;
+    pub use bevy::sprite::Anchor;
+    pub use bevy::text::{FontLoader, TextLayoutInfo};
+    pub use bevy::utils::BoxedFuture;
+    pub use bevy::window::WindowResolution;
+    pub use bevy_embedded_assets::EmbeddedAssetPlugin;
+    pub use bevy_mod_aseprite::{
+        Aseprite, AsepriteAnimation, AsepriteBundle, AsepritePlugin, AsepriteSystems, AsepriteTag,
+    };
+    pub use bevy_prototype_lyon::prelude::{
+        Fill, Geometry, GeometryBuilder, Path as TessPath, ShapeBundle, ShapePlugin, Stroke,
+    };
+    pub use bevy_prototype_lyon::shapes;
+    pub use lyon_tessellation as tess;
+    pub use rand::rngs::StdRng;
+    pub use rand::seq::SliceRandom;
+    pub use rand::{Rng, RngCore, SeedableRng};
+    pub use rand_distr::{Beta, Distribution};
+    pub use serde::{Deserialize, Serialize};
+
+    pub mod sprites {
+        use bevy_mod_aseprite::aseprite;
+        aseprite!(pub Paladin, "player_paladin.ase");
+        aseprite!(pub Crusader, "player_crusader.ase");
+    }
+
+    pub use crate::ape::*;
+    pub use crate::attacks::*;
+    pub use crate::boss::*;
+    pub use crate::common::*;
+    pub use crate::eth::*;
+    pub use crate::headless::*;
+    pub use crate::highscore::*;
+    pub use crate::inputs::*;
+    pub use crate::loot::*;
+    pub use crate::minion::*;
+    pub use crate::nft::*;
+    pub use crate::pause::*;
+    pub use crate::player::*;
+    pub use crate::projectile::*;
+    pub use crate::replay::*;
+    pub use crate::tuning::*;
+    pub use crate::wave::*;
+    pub use crate::AngryApesPlugin;
+
+    pub const GLOBAL_WIDTH: f32 = 1200.; // matches background.png width
+    pub const GLOBAL_HEIGHT: f32 = 600.; // matches background.png height
+    pub const PROJECTION_SCALE: f32 = 300.;
+}
+
+pub use crate::ape::ApePlugin;
+pub use crate::attacks::AttacksPlugin;
+pub use crate::boss::BossPlugin;
+pub use crate::eth::EthPlugin;
+pub use crate::headless::HeadlessPlugin;
+pub use crate::highscore::HighScorePlugin;
+pub use crate::inputs::InputPlugin;
+pub use crate::loot::LootPlugin;
+pub use crate::minion::MinionPlugin;
+pub use crate::nft::NftPlugin;
+pub use crate::pause::PausePlugin;
+pub use crate::player::PlayerPlugin;
+pub use crate::projectile::ProjectilePlugin;
+pub use crate::replay::ReplayPlugin;
+pub use crate::tuning::TuningPlugin;
+pub use crate::wave::WavePlugin;
+
+use crate::prelude::*;
+
+/// Wires the whole game: loading, game state flow and every gameplay plugin.
+///
+/// It expects the rendering side (`DefaultPlugins`, `ShapePlugin`, `AsepritePlugin`) to be
+/// added by the application, or [`HeadlessPlugin`] when running without a window.
+pub struct AngryApesPlugin;
+
+impl Plugin for AngryApesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AsepriteHandles>()
+            .init_resource::<Score>()
+            .init_resource::<GameRng>()
+            .add_state::<AppState>()
+            .add_plugins((
+                TuningPlugin,
+                InputPlugin,
+                PlayerPlugin,
+                EthPlugin,
+                LootPlugin,
+                ApePlugin,
+                AttacksPlugin,
+                ProjectilePlugin,
+                MinionPlugin,
+                NftPlugin,
+                BossPlugin,
+                PausePlugin,
+                HighScorePlugin,
+                WavePlugin,
+            ))
+            // Loading related systems
+            .add_systems(OnEnter(AppState::Loading), load_assets)
+            .add_systems(Update, check_assets.run_if(in_state(AppState::Loading)))
+            .add_systems(OnExit(AppState::Loading), setup)
+            // Title related systems
+            .add_systems(
+                OnEnter(AppState::Title),
+                (despawn_game_state, show_title_screen),
+            )
+            .add_systems(Update, title_screen.run_if(in_state(AppState::Title)))
+            .add_systems(
+                OnExit(AppState::Title),
+                (respawn_game_state, hide_title_screen),
+            )
+            // Gameover related systems
+            .add_systems(OnEnter(AppState::GameOver), despawn_game_state)
+            .add_systems(Update, gameover_screen.run_if(in_state(AppState::GameOver)))
+            .add_systems(OnExit(AppState::GameOver), respawn_game_state);
+    }
+}
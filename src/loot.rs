@@ -0,0 +1,170 @@
+use crate::prelude::*;
+
+//////////////////////////////////////// Plugin ////////////////////////////////////////
+
+pub struct LootPlugin;
+
+impl Plugin for LootPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (fall_loot, player_collects_power_ups).run_if(in_state(AppState::InGame)),
+        );
+    }
+}
+
+/////////////////////////////////////// Spawners ///////////////////////////////////////
+
+/// Rolls every entry of `loot`, the drops fall from `position` side by side.
+pub fn spawn_loot(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    eth_handle: &EthHandle,
+    rng: &mut GameRng,
+    position: Vec2,
+    loot: &[LootEntry],
+) {
+    let drops = loot
+        .iter()
+        .filter(|entry| rng.gen::<f32>() < entry.chance)
+        .map(|entry| entry.drop)
+        .collect::<Vec<_>>();
+
+    let wall = GLOBAL_WIDTH / 2. - 60.;
+    for (i, drop) in drops.iter().enumerate() {
+        let offset = (i as f32 - (drops.len() - 1) as f32 / 2.) * 50.;
+        let x = (position.x + offset).clamp(-wall, wall);
+        let translation = Vec3::new(x, position.y, 20.);
+
+        let dropped = match *drop {
+            LootDrop::Eth(quantity) => spawn_eth(commands, translation, quantity, eth_handle),
+            LootDrop::PowerUp(power_up) => {
+                spawn_power_up(commands, asset_server, translation, power_up)
+            }
+        };
+        commands
+            .entity(dropped)
+            .insert((Falling, Gravity { vy: 200. }));
+    }
+}
+
+pub fn spawn_power_up(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    translation: Vec3,
+    power_up: PowerUp,
+) -> Entity {
+    let (texture, scale, color) = match power_up {
+        PowerUp::Heart => ("heart_icon.png", 0.1, Color::WHITE),
+        PowerUp::Upgrade => ("eth_icon.png", 0.8, Color::GOLD),
+    };
+
+    commands
+        .spawn(SpriteBundle {
+            texture: asset_server.load(texture),
+            transform: Transform {
+                translation,
+                scale: Vec3::splat(scale),
+                ..default()
+            },
+            sprite: Sprite { color, ..default() },
+            ..default()
+        })
+        .insert(power_up)
+        .id()
+}
+
+////////////////////////////////////// Components //////////////////////////////////////
+
+/// One line of the loot table of an ape kind.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LootEntry {
+    pub drop: LootDrop,
+    /// Odds of the drop, each entry is rolled on its own
+    #[serde(default = "LootEntry::always")]
+    pub chance: f32,
+}
+
+impl LootEntry {
+    fn always() -> f32 {
+        1.
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub enum LootDrop {
+    Eth(f32),
+    PowerUp(PowerUp),
+}
+
+/// Picked up like ETH, with an immediate effect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component, Deserialize)]
+pub enum PowerUp {
+    /// Gives back a life chunk
+    Heart,
+    /// Fills the ETH gauge
+    Upgrade,
+}
+
+/// Loot still in the air, it can only be picked up once on the floor.
+#[derive(Component)]
+pub struct Falling;
+
+impl Falling {
+    /// Where loot lands, like the ETH lying on the floor
+    pub const FLOOR: f32 = -222.;
+}
+
+/////////////////////////////////////// Systems ////////////////////////////////////////
+
+pub fn fall_loot(
+    time: Res<Time>,
+    tuning: Res<PlayerTuning>,
+    mut commands: Commands,
+    mut loot_q: Query<(Entity, &mut Transform, &mut Gravity), With<Falling>>,
+) {
+    for (id, mut transform, mut gravity) in loot_q.iter_mut() {
+        gravity.fall(&mut transform, tuning.gravity, time.delta_seconds());
+        if transform.translation.y < Falling::FLOOR {
+            transform.translation.y = Falling::FLOOR;
+            commands.entity(id).remove::<(Falling, Gravity)>();
+        }
+    }
+}
+
+pub fn player_collects_power_ups(
+    tuning: Res<PlayerTuning>,
+    asset_server: Res<AssetServer>,
+    mut commands: Commands,
+    mut ev_unit_changed: EventWriter<UnitChanged>,
+    mut player_q: Query<(Entity, &Transform, &mut EthOwned), With<Player>>,
+    mut health_q: Query<&mut LifeChunks, With<LifeHud>>,
+    power_ups_q: Query<(Entity, &PowerUp, &Transform), Without<Falling>>,
+) {
+    let (player, player_transform, mut player_eth) = player_q.single_mut();
+    let player_x = player_transform.translation.x;
+    let player_y = player_transform.translation.y;
+
+    for (id, power_up, transform) in power_ups_q.iter() {
+        if (player_x - transform.translation.x).abs() >= 30. || player_y >= -100. {
+            continue;
+        }
+
+        match power_up {
+            PowerUp::Heart => {
+                let mut chunks = health_q.single_mut();
+                if chunks.len() < tuning.life_chunks {
+                    let chunk = spawn_life_chunk(&mut commands, &asset_server, chunks.len());
+                    chunks.push(chunk);
+                }
+            }
+            PowerUp::Upgrade => {
+                player_eth.current = player_eth.max;
+                ev_unit_changed
+                    .send(UnitChanged::entity(player).new_condition(UnitCondition::Upgraded));
+            }
+        }
+        commands.entity(id).despawn();
+    }
+}
@@ -1,72 +1,28 @@
-#![allow(clippy::type_complexity)]
-#![allow(clippy::too_many_arguments)]
-#![allow(clippy::single_component_path_imports)]
+use angry_apes::prelude::*;
 
-mod ape;
-mod common;
-mod eth;
-mod inputs;
-mod player;
-
-mod prelude {
-    pub use std::collections::{HashMap, HashSet};
+fn main() {
+    let args = std::env::args().collect::<Vec<_>>();
+    let mut app = App::new();
 
-    #[cfg(target_arch = "wasm32")]
-    pub use instant::{Duration, Instant};
-    #[cfg(not(target_arch = "wasm32"))]
-    pub use std::time::{Duration, Instant};
+    if let Some(seed) = arg_value(&args, "--seed") {
+        let seed = seed.parse().expect("--seed expects an unsigned integer");
+        app.insert_resource(GameRng::from_seed(seed));
+    }
 
     #[cfg(not(target_arch = "wasm32"))]
-    #[cfg(debug_assertions)]
-    #[allow(unused_imports)]
-    use bevy_dylib;
-
-    pub use bevy::asset::LoadState;
-    pub use bevy::ecs::event::Events;
-    pub use bevy::input::gamepad::{
-        Gamepad, GamepadAxisType, GamepadButton, GamepadConnection, GamepadConnectionEvent,
-        GamepadEvent,
-    };
-    pub use bevy::prelude::*;
-    pub use bevy::render::camera::OrthographicProjection;
-    pub use bevy::render::camera::ScalingMode;
-    pub use bevy::text::TextLayoutInfo;
-    pub use bevy::window::WindowResolution;
-    pub use bevy_embedded_assets::EmbeddedAssetPlugin;
-    pub use bevy_mod_aseprite::{
-        Aseprite, AsepriteAnimation, AsepriteBundle, AsepritePlugin, AsepriteSystems, AsepriteTag,
-    };
-    pub use bevy_prototype_lyon::prelude::{
-        Fill, Geometry, GeometryBuilder, Path as TessPath, ShapeBundle, ShapePlugin, Stroke,
-    };
-    pub use bevy_prototype_lyon::shapes;
-    pub use lyon_tessellation as tess;
-    pub use rand::seq::SliceRandom;
-    pub use rand_distr::{Beta, Distribution};
-
-    pub mod sprites {
-        use bevy_mod_aseprite::aseprite;
-        aseprite!(pub Paladin, "player_paladin.ase");
-        aseprite!(pub Crusader, "player_crusader.ase");
+    if let Some(path) = arg_value(&args, "--record") {
+        app.add_plugins(ReplayPlugin::Record(path.into()));
+    } else if let Some(path) = arg_value(&args, "--replay") {
+        app.add_plugins(ReplayPlugin::Playback(path.into()));
     }
 
-    pub use crate::ape::*;
-    pub use crate::common::*;
-    pub use crate::eth::*;
-    pub use crate::inputs::*;
-    pub use crate::player::*;
-
-    pub const GLOBAL_WIDTH: f32 = 1200.; // matches background.png width
-    pub const GLOBAL_HEIGHT: f32 = 600.; // matches background.png height
-    pub const PROJECTION_SCALE: f32 = 300.;
-}
-
-use crate::prelude::*;
-
-fn main() {
-    App::new()
+    if args.iter().any(|arg| arg == "--headless") {
+        app.insert_resource(HighScores::default())
+            .add_plugins((HeadlessPlugin, bevy::log::LogPlugin::default()))
+            .add_systems(OnEnter(AppState::GameOver), exit_on_gameover);
+    } else {
         // Setup plugins
-        .add_plugins((
+        app.add_plugins((
             EmbeddedAssetPlugin::default(),
             DefaultPlugins
                 .set(WindowPlugin {
@@ -81,116 +37,20 @@ fn main() {
                 .build(),
             ShapePlugin,
             AsepritePlugin,
-        ))
-        // Initialize game
-        .init_resource::<AsepriteHandles>()
-        .init_resource::<Events<UnitChanged>>()
-        .init_resource::<Events<UnitAttack>>()
-        .init_resource::<InputKind>()
-        .init_resource::<Score>()
-        .add_state::<AppState>()
-        // Game related systems
-        .add_systems(OnEnter(AppState::Loading), load_assets)
-        .add_systems(Update, check_assets.run_if(in_state(AppState::Loading)))
-        .add_systems(OnExit(AppState::Loading), setup)
-        .add_systems(
-            Update,
-            // Input related systems
-            (gamepad_connection_events.before(handle_input), handle_input)
-                .run_if(in_state(AppState::InGame)),
-        )
-        .add_systems(
-            Update,
-            // Player related systems
-            (
-                move_units,
-                fall_units,
-                tick_dashes,
-                cooldown_dashes,
-                transition_units.before(AsepriteSystems::Animate),
-                unit_attacks_ape.after(transition_units),
-                reorient_units_on_sprite_change,
-                update_units.after(transition_units),
-            )
-                .run_if(in_state(AppState::InGame)),
-        )
-        .add_systems(
-            Update,
-            // Eth related systems
-            (
-                make_eth,
-                animate_eth,
-                player_collects_eth,
-                player_eth_gauge,
-                decay_player_eth,
-            )
-                .run_if(in_state(AppState::InGame)),
-        )
-        .add_systems(
-            Update,
-            // Ape related systems
-            (
-                make_ape,
-                move_apes,
-                trigger_ape_attack,
-                ape_attacks_player_collision,
-                animate_apes_wounds,
-                animate_apes_attacks,
-                display_dead_apes_hud,
-            )
-                .run_if(in_state(AppState::InGame)),
-        )
-        // Gameover related systems
-        .add_systems(OnEnter(AppState::GameOver), despawn_game_state)
-        .add_systems(Update, gameover_screen.run_if(in_state(AppState::GameOver)))
-        .add_systems(OnExit(AppState::GameOver), respawn_game_state)
-        .run();
-}
-
-fn load_assets(mut aseprite_handles: ResMut<AsepriteHandles>, asset_server: Res<AssetServer>) {
-    for asprite_path in [sprites::Paladin::PATH, sprites::Crusader::PATH] {
-        let aseprite = asset_server.load(asprite_path);
-        aseprite_handles.insert(asprite_path, aseprite);
+        ));
     }
-}
 
-fn check_assets(
-    aseprite_handles: ResMut<AsepriteHandles>,
-    asset_server: Res<AssetServer>,
-    mut state: ResMut<NextState<AppState>>,
-) {
-    aseprite_handles
-        .iter()
-        .all(|(_, handle)| {
-            matches!(
-                asset_server.get_load_state(handle.id()),
-                Some(LoadState::Loaded)
-            )
-        })
-        .then(|| state.set(AppState::InGame));
+    app.add_plugins(AngryApesPlugin).run();
 }
 
-fn setup(
-    mut commands: Commands,
-    asset_server: Res<AssetServer>,
-    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
-    aseprite_handles: Res<AsepriteHandles>,
-    aseprites: Res<Assets<Aseprite>>,
-) {
-    init_eth(&mut commands, &asset_server, &mut texture_atlases);
-
-    let font_handle = spawn_font(&mut commands, &asset_server);
-    let ape_icon_h = init_ape_icon(&mut commands, &asset_server);
-    spawn_gameover_screen(&mut commands, &asset_server, &font_handle, &ape_icon_h);
-
-    spawn_camera(&mut commands);
+fn arg_value<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|arg| arg == name)
+        .and_then(|i| args.get(i + 1))
+        .map(String::as_str)
+}
 
-    spawn_game_state(
-        &mut commands,
-        &asset_server,
-        &mut texture_atlases,
-        &font_handle,
-        &aseprite_handles,
-        &aseprites,
-    );
+fn exit_on_gameover(score: Res<Score>, mut app_exit: EventWriter<bevy::app::AppExit>) {
+    info!("Game over, {} apes killed", score.0);
+    app_exit.send(bevy::app::AppExit);
 }
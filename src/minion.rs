@@ -0,0 +1,176 @@
+use crate::prelude::*;
+
+//////////////////////////////////////// Plugin ////////////////////////////////////////
+
+pub struct MinionPlugin;
+
+impl Plugin for MinionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                apes_spawn_minions,
+                rush_minions,
+                minions_player_collision.after(rush_minions),
+                unit_attacks_minions.after(transition_units),
+            )
+                .run_if(in_state(AppState::InGame)),
+        );
+    }
+}
+
+/////////////////////////////////////// Spawners ///////////////////////////////////////
+
+/// A tiny version of `kind` running on the platform from `x`.
+pub fn spawn_minion(commands: &mut Commands, asset_server: &AssetServer, kind: ApeKind, x: f32) {
+    commands.spawn((
+        SpriteBundle {
+            texture: asset_server.load(format!("{}.png", kind.name())),
+            transform: Transform {
+                scale: Vec3::splat(Minion::SCALE),
+                translation: Vec3::new(x, Minion::Y, 12.),
+                ..default()
+            },
+            ..default()
+        },
+        Minion,
+    ));
+}
+
+////////////////////////////////////// Components //////////////////////////////////////
+
+/// Runs at the player along the platform, hurts on contact and dies in one hit.
+#[derive(Component)]
+pub struct Minion;
+
+impl Minion {
+    const SCALE: f32 = 0.15;
+    /// Its feet on the platform, like the player
+    pub const Y: f32 = -185.;
+    /// Distance to the player at which it hurts
+    pub const HALF_WIDTH: f32 = 25.;
+    /// Distance to the player at which an attack kills it
+    pub const REACH: f32 = 90.;
+}
+
+/// Paces the minions an ape sends at the player.
+#[derive(Component, Deref, DerefMut)]
+pub struct ApeMinions(Timer);
+
+impl ApeMinions {
+    pub fn new(tuning: &ApeMinionsTuning) -> Self {
+        Self(Timer::from_seconds(tuning.interval, TimerMode::Repeating))
+    }
+}
+
+/////////////////////////////////////// Systems ////////////////////////////////////////
+
+pub fn apes_spawn_minions(
+    time: Res<Time>,
+    tuning: Res<ApeTuning>,
+    asset_server: Res<AssetServer>,
+    mut commands: Commands,
+    mut apes_q: Query<
+        (&Transform, &ApeKind, &mut ApeMinions),
+        (With<Ape>, Without<ApeEntering>, Without<ApeStunned>),
+    >,
+    minions_q: Query<(), With<Minion>>,
+) {
+    let mut count = minions_q.iter().len();
+    for (transform, &kind, mut minions) in apes_q.iter_mut() {
+        minions.set_duration(Duration::from_secs_f32(tuning.minions.interval));
+        if minions.tick(time.delta()).just_finished() && count < tuning.minions.max {
+            spawn_minion(&mut commands, &asset_server, kind, transform.translation.x);
+            count += 1;
+        }
+    }
+}
+
+pub fn rush_minions(
+    time: Res<Time>,
+    tuning: Res<ApeTuning>,
+    player_q: Query<&Transform, (With<Player>, Without<Minion>)>,
+    mut minions_q: Query<(&mut Transform, &mut Sprite), With<Minion>>,
+) {
+    let player_x = player_q.single().translation.x;
+    let inc = tuning.minions.speed * time.delta_seconds();
+    for (mut transform, mut sprite) in minions_q.iter_mut() {
+        let to_player = player_x - transform.translation.x;
+        transform.translation.x += to_player.clamp(-inc, inc);
+        // Ape sprites look to the right
+        sprite.flip_x = to_player < 0.;
+    }
+}
+
+pub fn minions_player_collision(
+    mut commands: Commands,
+    mut ev_unit_changed: EventWriter<UnitChanged>,
+    minions_q: Query<(Entity, &Transform), With<Minion>>,
+    player_q: Query<(Entity, &Transform, &UnitState, &UnitCondition), With<Player>>,
+    mut health_q: Query<&mut LifeChunks, With<LifeHud>>,
+) {
+    let (player, player_transform, player_state, player_condition) = player_q.single();
+
+    if matches!(player_condition, UnitCondition::Upgraded)
+        || matches!(
+            player_state,
+            UnitState::Wound | UnitState::Die | UnitState::Dash | UnitState::Jump | UnitState::Fall
+        )
+    {
+        return;
+    }
+
+    let player_x = player_transform.translation.x;
+    for (id, transform) in minions_q.iter() {
+        if (transform.translation.x - player_x).abs() >= Minion::HALF_WIDTH {
+            continue;
+        }
+
+        wound_player(
+            &mut commands,
+            &mut ev_unit_changed,
+            player,
+            &mut health_q.single_mut(),
+        );
+        commands.entity(id).despawn_recursive();
+        return;
+    }
+}
+
+pub fn unit_attacks_minions(
+    tuning: Res<ApeTuning>,
+    asset_server: Res<AssetServer>,
+    eth_handle: Res<EthHandle>,
+    mut rng: ResMut<GameRng>,
+    mut commands: Commands,
+    mut ev_unit_attack: EventReader<UnitAttack>,
+    units_q: Query<&Transform>,
+    minions_q: Query<(Entity, &Transform), With<Minion>>,
+) {
+    let loot = [LootEntry {
+        drop: LootDrop::Eth(tuning.minions.eth),
+        chance: 1.,
+    }];
+
+    for &UnitAttack(unit) in ev_unit_attack.read() {
+        let Ok(unit_transform) = units_q.get(unit) else {
+            continue;
+        };
+
+        let unit_x = unit_transform.translation.x;
+        for (id, transform) in minions_q.iter() {
+            if (transform.translation.x - unit_x).abs() >= Minion::REACH {
+                continue;
+            }
+            commands.entity(id).despawn_recursive();
+            spawn_loot(
+                &mut commands,
+                &asset_server,
+                &eth_handle,
+                &mut rng,
+                transform.translation.truncate(),
+                &loot,
+            );
+        }
+    }
+}
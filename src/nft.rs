@@ -0,0 +1,591 @@
+use crate::prelude::*;
+
+//////////////////////////////////////// Plugin ////////////////////////////////////////
+
+pub struct NftPlugin;
+
+impl Plugin for NftPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ApeKilled>().add_systems(
+            Update,
+            (
+                fill_kill_feed,
+                animate_kill_feed.after(fill_kill_feed),
+                tint_traits_layers,
+            )
+                .run_if(in_state(AppState::InGame)),
+        );
+    }
+}
+
+/////////////////////////////////////// Spawners ///////////////////////////////////////
+
+/// Draws the traits of an ape over its sprite, the fur being its tint.
+pub fn spawn_ape_traits_layers(
+    commands: &mut Commands,
+    ape: Entity,
+    traits: &ApeTraits,
+    flank: Flank,
+) {
+    let facing = flank.facing_sign();
+    let layer = |commands: &mut Commands, builder: GeometryBuilder, x: f32, y: f32| {
+        commands
+            .spawn(ShapeBundle {
+                path: builder.build(),
+                spatial: SpatialBundle {
+                    transform: Transform::from_xyz(facing * x, y, 1.),
+                    ..default()
+                },
+                ..default()
+            })
+            .id()
+    };
+
+    let mut layers = vec![];
+
+    let hat = match traits.hat {
+        Hat::Bald => None,
+        Hat::Beanie => Some((
+            GeometryBuilder::new().add(&shapes::Ellipse {
+                radii: Vec2::new(90., 45.),
+                center: Vec2::ZERO,
+            }),
+            Color::rgb(0.2, 0.4, 0.8),
+        )),
+        Hat::Cap => Some((
+            GeometryBuilder::new()
+                .add(&shapes::Rectangle {
+                    extents: Vec2::new(160., 50.),
+                    origin: shapes::RectangleOrigin::Center,
+                })
+                .add(&shapes::Rectangle {
+                    extents: Vec2::new(90., 14.),
+                    origin: shapes::RectangleOrigin::CustomCenter(Vec2::new(facing * 110., -18.)),
+                }),
+            Color::rgb(0.85, 0.15, 0.15),
+        )),
+        Hat::Party => Some((
+            GeometryBuilder::new().add(&shapes::Polygon {
+                points: vec![
+                    Vec2::new(-60., -30.),
+                    Vec2::new(60., -30.),
+                    Vec2::new(0., 90.),
+                ],
+                closed: true,
+            }),
+            Color::rgb(0.9, 0.3, 0.9),
+        )),
+        Hat::Halo => Some((
+            GeometryBuilder::new().add(&shapes::Ellipse {
+                radii: Vec2::new(90., 18.),
+                center: Vec2::new(0., 30.),
+            }),
+            Color::rgba(1., 0.95, 0.5, 0.8),
+        )),
+    };
+    if let Some((builder, color)) = hat {
+        let hat = layer(commands, builder, 0., 240.);
+        commands.entity(hat).insert((
+            Fill::color(color),
+            Stroke::new(Color::BLACK, 3.),
+            TraitLayer {
+                fill: Some(color),
+                stroke: Some(Color::BLACK),
+            },
+        ));
+        layers.push(hat);
+    }
+
+    let eyes = match traits.eyes {
+        Eyes::Bored => None,
+        Eyes::Sleepy => Some((
+            GeometryBuilder::new().add(&shapes::Rectangle {
+                extents: Vec2::new(170., 16.),
+                origin: shapes::RectangleOrigin::Center,
+            }),
+            Color::rgb(0.45, 0.3, 0.2),
+        )),
+        Eyes::Hypnotized => Some((
+            GeometryBuilder::new()
+                .add(&shapes::Circle {
+                    radius: 22.,
+                    center: Vec2::new(-45., 0.),
+                })
+                .add(&shapes::Circle {
+                    radius: 22.,
+                    center: Vec2::new(45., 0.),
+                }),
+            Color::rgb(0.3, 0.9, 0.4),
+        )),
+        Eyes::Laser => Some((
+            GeometryBuilder::new()
+                .add(&shapes::Circle {
+                    radius: 16.,
+                    center: Vec2::new(-45., 0.),
+                })
+                .add(&shapes::Circle {
+                    radius: 16.,
+                    center: Vec2::new(45., 0.),
+                }),
+            Color::RED,
+        )),
+    };
+    if let Some((builder, color)) = eyes {
+        let eyes = layer(commands, builder, 60., 85.);
+        commands.entity(eyes).insert((
+            Fill::color(color),
+            TraitLayer {
+                fill: Some(color),
+                stroke: None,
+            },
+        ));
+        layers.push(eyes);
+    }
+
+    let accessory = match traits.accessory {
+        Accessory::Nothing => None,
+        Accessory::Earring => Some((
+            GeometryBuilder::new().add(&shapes::Circle {
+                radius: 10.,
+                center: Vec2::ZERO,
+            }),
+            -120.,
+            20.,
+        )),
+        Accessory::Cigar => Some((
+            GeometryBuilder::new().add(&shapes::Rectangle {
+                extents: Vec2::new(80., 14.),
+                origin: shapes::RectangleOrigin::Center,
+            }),
+            140.,
+            -45.,
+        )),
+        Accessory::Chain => Some((
+            GeometryBuilder::new().add(&shapes::Ellipse {
+                radii: Vec2::new(110., 30.),
+                center: Vec2::ZERO,
+            }),
+            0.,
+            -200.,
+        )),
+    };
+    if let Some((builder, x, y)) = accessory {
+        let accessory = layer(commands, builder, x, y);
+        let color = match traits.accessory {
+            Accessory::Cigar => Color::rgb(0.45, 0.25, 0.1),
+            _ => Color::GOLD,
+        };
+        match traits.accessory {
+            Accessory::Chain => commands.entity(accessory).insert((
+                Stroke::new(color, 10.),
+                TraitLayer {
+                    fill: None,
+                    stroke: Some(color),
+                },
+            )),
+            _ => commands.entity(accessory).insert((
+                Fill::color(color),
+                TraitLayer {
+                    fill: Some(color),
+                    stroke: None,
+                },
+            )),
+        };
+        layers.push(accessory);
+    }
+
+    commands.entity(ape).push_children(&layers);
+}
+
+pub fn spawn_kill_feed(commands: &mut Commands) {
+    commands.spawn((
+        SpatialBundle::from_transform(Transform::from_xyz(KillFeed::X, KillFeed::Y, 998.)),
+        KillFeed,
+    ));
+}
+
+////////////////////////////////////// Components //////////////////////////////////////
+
+/// How rare a trait is, the rarest trait of an ape gives its own rarity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Rarity {
+    Common,
+    Rare,
+    Epic,
+    Legendary,
+}
+
+impl Rarity {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Common => "Common",
+            Self::Rare => "Rare",
+            Self::Epic => "Epic",
+            Self::Legendary => "Legendary",
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        match self {
+            Self::Common => Color::WHITE,
+            Self::Rare => Color::rgb(0.4, 0.7, 1.),
+            Self::Epic => Color::rgb(0.8, 0.45, 1.),
+            Self::Legendary => Color::GOLD,
+        }
+    }
+
+    /// How much the stat a trait drives is improved
+    pub fn boost(&self) -> f32 {
+        match self {
+            Self::Common => 1.,
+            Self::Rare => 1.1,
+            Self::Epic => 1.25,
+            Self::Legendary => 1.5,
+        }
+    }
+
+    fn roll(rng: &mut GameRng, tuning: &ApeTraitsTuning) -> Self {
+        let roll = rng.gen::<f32>();
+        if roll < tuning.legendary {
+            Self::Legendary
+        } else if roll < tuning.legendary + tuning.epic {
+            Self::Epic
+        } else if roll < tuning.legendary + tuning.epic + tuning.rare {
+            Self::Rare
+        } else {
+            Self::Common
+        }
+    }
+}
+
+/// A family of traits, each variant having its own rarity.
+pub trait Trait: Copy + 'static {
+    const ALL: &'static [Self];
+
+    fn rarity(&self) -> Rarity;
+
+    /// Picks a trait of a rolled rarity, or of the closest lower one without any.
+    fn roll(rng: &mut GameRng, tuning: &ApeTraitsTuning) -> Self {
+        let mut rarity = Rarity::roll(rng, tuning);
+        loop {
+            let candidates = Self::ALL
+                .iter()
+                .filter(|t| t.rarity() == rarity)
+                .collect::<Vec<_>>();
+            if let Some(&&picked) = candidates.choose(rng) {
+                return picked;
+            }
+            rarity = match rarity {
+                Rarity::Legendary => Rarity::Epic,
+                Rarity::Epic => Rarity::Rare,
+                Rarity::Rare | Rarity::Common => Rarity::Common,
+            };
+        }
+    }
+}
+
+/// Drives the life of the ape.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fur {
+    Brown,
+    Tan,
+    Dmt,
+    Zombie,
+    Golden,
+}
+
+impl Trait for Fur {
+    const ALL: &'static [Self] = &[
+        Self::Brown,
+        Self::Tan,
+        Self::Dmt,
+        Self::Zombie,
+        Self::Golden,
+    ];
+
+    fn rarity(&self) -> Rarity {
+        match self {
+            Self::Brown | Self::Tan => Rarity::Common,
+            Self::Dmt => Rarity::Rare,
+            Self::Zombie => Rarity::Epic,
+            Self::Golden => Rarity::Legendary,
+        }
+    }
+}
+
+impl Fur {
+    /// Tint of the ape sprite
+    pub fn color(&self) -> Color {
+        match self {
+            Self::Brown => Color::WHITE,
+            Self::Tan => Color::rgb(1., 0.9, 0.75),
+            Self::Dmt => Color::rgb(0.8, 0.75, 1.),
+            Self::Zombie => Color::rgb(0.65, 0.9, 0.6),
+            Self::Golden => Color::rgb(1., 0.85, 0.35),
+        }
+    }
+}
+
+/// Drives the ETH dropped by the ape.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hat {
+    Bald,
+    Beanie,
+    Cap,
+    Party,
+    Halo,
+}
+
+impl Trait for Hat {
+    const ALL: &'static [Self] = &[Self::Bald, Self::Beanie, Self::Cap, Self::Party, Self::Halo];
+
+    fn rarity(&self) -> Rarity {
+        match self {
+            Self::Bald | Self::Beanie => Rarity::Common,
+            Self::Cap => Rarity::Rare,
+            Self::Party => Rarity::Epic,
+            Self::Halo => Rarity::Legendary,
+        }
+    }
+}
+
+/// Drives how quick the ape charges its lasers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Eyes {
+    Bored,
+    Sleepy,
+    Hypnotized,
+    Laser,
+}
+
+impl Trait for Eyes {
+    const ALL: &'static [Self] = &[Self::Bored, Self::Sleepy, Self::Hypnotized, Self::Laser];
+
+    fn rarity(&self) -> Rarity {
+        match self {
+            Self::Bored | Self::Sleepy => Rarity::Common,
+            Self::Hypnotized => Rarity::Rare,
+            Self::Laser => Rarity::Legendary,
+        }
+    }
+}
+
+/// Drives how fast the ape moves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Accessory {
+    Nothing,
+    Earring,
+    Cigar,
+    Chain,
+}
+
+impl Trait for Accessory {
+    const ALL: &'static [Self] = &[Self::Nothing, Self::Earring, Self::Cigar, Self::Chain];
+
+    fn rarity(&self) -> Rarity {
+        match self {
+            Self::Nothing | Self::Earring => Rarity::Common,
+            Self::Cigar => Rarity::Rare,
+            Self::Chain => Rarity::Epic,
+        }
+    }
+}
+
+/// What makes an ape unique, its stats are derived from it.
+#[derive(Debug, Clone, Copy, PartialEq, Component)]
+pub struct ApeTraits {
+    pub token_id: u32,
+    pub fur: Fur,
+    pub hat: Hat,
+    pub eyes: Eyes,
+    pub accessory: Accessory,
+}
+
+impl ApeTraits {
+    pub const SUPPLY: u32 = 10_000;
+
+    pub fn roll(rng: &mut GameRng, tuning: &ApeTraitsTuning) -> Self {
+        Self {
+            token_id: rng.gen_range(0..Self::SUPPLY),
+            fur: Fur::roll(rng, tuning),
+            hat: Hat::roll(rng, tuning),
+            eyes: Eyes::roll(rng, tuning),
+            accessory: Accessory::roll(rng, tuning),
+        }
+    }
+
+    pub fn rarity(&self) -> Rarity {
+        [
+            self.fur.rarity(),
+            self.hat.rarity(),
+            self.eyes.rarity(),
+            self.accessory.rarity(),
+        ]
+        .into_iter()
+        .max()
+        .unwrap()
+    }
+
+    pub fn life_scale(&self) -> f32 {
+        self.fur.rarity().boost()
+    }
+
+    pub fn eth_scale(&self) -> f32 {
+        self.hat.rarity().boost()
+    }
+
+    /// Rarer eyes charge quicker
+    pub fn laser_charge_scale(&self) -> f32 {
+        1. / self.eyes.rarity().boost()
+    }
+
+    pub fn speed_scale(&self) -> f32 {
+        self.accessory.rarity().boost()
+    }
+
+    /// The loot table of the ape kind, with the ETH boosted by the hat.
+    pub fn loot(&self, loot: &[LootEntry]) -> Vec<LootEntry> {
+        loot.iter()
+            .map(|&entry| match entry.drop {
+                LootDrop::Eth(quantity) => LootEntry {
+                    drop: LootDrop::Eth(quantity * self.eth_scale()),
+                    ..entry
+                },
+                LootDrop::PowerUp(_) => entry,
+            })
+            .collect()
+    }
+
+    /// Like `#1234 Rare King`
+    pub fn title(&self, kind: ApeKind) -> String {
+        format!(
+            "#{} {} {}",
+            self.token_id,
+            self.rarity().name(),
+            kind.title()
+        )
+    }
+}
+
+/// A trait drawn over an ape, tinted along with its body.
+#[derive(Component)]
+pub struct TraitLayer {
+    /// Colors of the layer over an untinted ape
+    pub fill: Option<Color>,
+    pub stroke: Option<Color>,
+}
+
+#[derive(Event)]
+pub struct ApeKilled {
+    pub kind: ApeKind,
+    pub traits: ApeTraits,
+}
+
+/// The latest kills, newest first.
+#[derive(Component)]
+pub struct KillFeed;
+
+impl KillFeed {
+    const X: f32 = 580.;
+    const Y: f32 = 225.;
+    const LINE_HEIGHT: f32 = 26.;
+    const MAX_ENTRIES: usize = 4;
+}
+
+#[derive(Component, Deref, DerefMut)]
+pub struct KillFeedEntry(Timer);
+
+impl KillFeedEntry {
+    const DURATION: f32 = 4.;
+}
+
+/////////////////////////////////////// Systems ////////////////////////////////////////
+
+pub fn fill_kill_feed(
+    mut commands: Commands,
+    mut ev_ape_killed: EventReader<ApeKilled>,
+    font_handle: Res<FontHandle>,
+    feed_q: Query<Entity, With<KillFeed>>,
+) {
+    let Ok(feed) = feed_q.get_single() else {
+        return;
+    };
+
+    for ApeKilled { kind, traits } in ev_ape_killed.read() {
+        let entry = commands
+            .spawn(Text2dBundle {
+                text: Text::from_section(
+                    format!("{} killed", traits.title(*kind)),
+                    TextStyle {
+                        font: (**font_handle).clone(),
+                        font_size: 22.0,
+                        color: traits.rarity().color(),
+                    },
+                )
+                .with_alignment(TextAlignment::Right),
+                text_anchor: Anchor::CenterRight,
+                ..default()
+            })
+            .insert(KillFeedEntry(Timer::from_seconds(
+                KillFeedEntry::DURATION,
+                TimerMode::Once,
+            )))
+            .id();
+        commands.entity(feed).push_children(&[entry]);
+    }
+}
+
+/// Stacks the entries below each other and fades them away.
+pub fn animate_kill_feed(
+    time: Res<Time>,
+    mut commands: Commands,
+    feed_q: Query<&Children, With<KillFeed>>,
+    mut entries_q: Query<(&mut KillFeedEntry, &mut Transform, &mut Text)>,
+) {
+    let Ok(entries) = feed_q.get_single() else {
+        return;
+    };
+
+    for (i, &entry) in entries.iter().rev().enumerate() {
+        let Ok((mut timer, mut transform, mut text)) = entries_q.get_mut(entry) else {
+            continue;
+        };
+        timer.tick(time.delta());
+        if timer.finished() || i >= KillFeed::MAX_ENTRIES {
+            commands.entity(entry).despawn_recursive();
+        } else {
+            transform.translation.y = -(i as f32) * KillFeed::LINE_HEIGHT;
+            text.sections[0]
+                .style
+                .color
+                .set_a(timer.percent_left().min(0.25) * 4.);
+        }
+    }
+}
+
+/// Gives the traits layers the tint and transparency of the ape body, its fur aside.
+pub fn tint_traits_layers(
+    apes_q: Query<(&Sprite, &ApeTraits, &Children), Changed<Sprite>>,
+    mut layers_q: Query<(&TraitLayer, Option<&mut Fill>, Option<&mut Stroke>)>,
+) {
+    for (sprite, traits, children) in apes_q.iter() {
+        let tint = if sprite.color.with_a(1.) == traits.fur.color() {
+            Color::WHITE.with_a(sprite.color.a())
+        } else {
+            sprite.color
+        };
+        let tint = Vec4::from(tint.as_rgba_f32());
+
+        for &child in children.iter() {
+            let Ok((layer, fill, stroke)) = layers_q.get_mut(child) else {
+                continue;
+            };
+            if let (Some(mut fill), Some(color)) = (fill, layer.fill) {
+                fill.color = color * tint;
+            }
+            if let (Some(mut stroke), Some(color)) = (stroke, layer.stroke) {
+                stroke.color = color * tint;
+            }
+        }
+    }
+}
@@ -0,0 +1,184 @@
+use crate::prelude::*;
+
+//////////////////////////////////////// Plugin ////////////////////////////////////////
+
+pub struct PausePlugin;
+
+impl Plugin for PausePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PauseSelection>()
+            .add_systems(Update, pause_game.run_if(in_state(AppState::InGame)))
+            .add_systems(OnEnter(AppState::Paused), (pause_time, show_pause_menu))
+            .add_systems(Update, pause_menu.run_if(in_state(AppState::Paused)))
+            .add_systems(OnExit(AppState::Paused), (unpause_time, hide_pause_menu))
+            .add_systems(
+                OnExit(AppState::Paused),
+                (despawn_game_state, respawn_game_state, clear_restart)
+                    .run_if(resource_exists::<RestartRequested>()),
+            );
+    }
+}
+
+/////////////////////////////////////// Spawners ///////////////////////////////////////
+
+pub fn spawn_pause_menu(commands: &mut Commands, font_handle: &Handle<Font>) {
+    let style = |font_size| TextStyle {
+        font: font_handle.clone(),
+        font_size,
+        color: Color::WHITE,
+    };
+
+    commands
+        .spawn(SpriteBundle {
+            sprite: Sprite {
+                color: Color::rgba(0., 0., 0., 0.6),
+                custom_size: Some(Vec2::new(GLOBAL_WIDTH, GLOBAL_HEIGHT)),
+                ..default()
+            },
+            visibility: Visibility::Hidden,
+            transform: Transform::from_xyz(0., 0., 999.5),
+            ..default()
+        })
+        .insert(PauseElements)
+        .with_children(|parent| {
+            parent.spawn(Text2dBundle {
+                text: Text::from_section("Paused", style(60.))
+                    .with_alignment(TextAlignment::Center),
+                visibility: Visibility::Inherited,
+                transform: Transform::from_xyz(0., 120., 0.1),
+                ..default()
+            });
+
+            let mut offset = 20.;
+            for choice in PauseChoice::ALL {
+                parent
+                    .spawn(Text2dBundle {
+                        text: Text::from_section(choice.label(), style(30.))
+                            .with_alignment(TextAlignment::Center),
+                        visibility: Visibility::Inherited,
+                        transform: Transform::from_xyz(0., offset, 0.1),
+                        ..default()
+                    })
+                    .insert(choice);
+                offset -= 50.;
+            }
+        });
+}
+
+////////////////////////////////////// Components //////////////////////////////////////
+
+#[derive(Component)]
+pub struct PauseElements;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Component)]
+pub enum PauseChoice {
+    #[default]
+    Resume,
+    Restart,
+    Quit,
+}
+
+impl PauseChoice {
+    pub const ALL: [Self; 3] = [Self::Resume, Self::Restart, Self::Quit];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Resume => "Resume",
+            Self::Restart => "Restart",
+            Self::Quit => "Quit   to   title",
+        }
+    }
+
+    pub fn previous(&self) -> Self {
+        match self {
+            Self::Resume => Self::Quit,
+            Self::Restart => Self::Resume,
+            Self::Quit => Self::Restart,
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            Self::Resume => Self::Restart,
+            Self::Restart => Self::Quit,
+            Self::Quit => Self::Resume,
+        }
+    }
+}
+
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct PauseSelection(PauseChoice);
+
+/// Marks that the game state must be rebuilt when leaving the pause menu.
+#[derive(Resource)]
+pub struct RestartRequested;
+
+/////////////////////////////////////// Systems ////////////////////////////////////////
+
+pub fn pause_game(
+    input_kind: Res<InputKind>,
+    keys: Res<Input<KeyCode>>,
+    buttons: Res<Input<GamepadButton>>,
+    mut app_state: ResMut<NextState<AppState>>,
+) {
+    if let Some(MenuAction::Pause) = MenuAction::detect(&input_kind, &keys, &buttons) {
+        app_state.set(AppState::Paused);
+    }
+}
+
+pub fn pause_time(mut time: ResMut<Time<Virtual>>) {
+    time.pause();
+}
+
+pub fn unpause_time(mut time: ResMut<Time<Virtual>>) {
+    time.unpause();
+}
+
+pub fn show_pause_menu(
+    mut selection: ResMut<PauseSelection>,
+    mut pause_elements_q: Query<&mut Visibility, With<PauseElements>>,
+) {
+    **selection = PauseChoice::default();
+    *pause_elements_q.single_mut() = Visibility::Visible;
+}
+
+pub fn hide_pause_menu(mut pause_elements_q: Query<&mut Visibility, With<PauseElements>>) {
+    *pause_elements_q.single_mut() = Visibility::Hidden;
+}
+
+pub fn pause_menu(
+    mut commands: Commands,
+    input_kind: Res<InputKind>,
+    keys: Res<Input<KeyCode>>,
+    buttons: Res<Input<GamepadButton>>,
+    mut selection: ResMut<PauseSelection>,
+    mut app_state: ResMut<NextState<AppState>>,
+    mut entries_q: Query<(&PauseChoice, &mut Text)>,
+) {
+    match MenuAction::detect(&input_kind, &keys, &buttons) {
+        Some(MenuAction::Up) => **selection = selection.previous(),
+        Some(MenuAction::Down) => **selection = selection.next(),
+        Some(MenuAction::Pause) => app_state.set(AppState::InGame),
+        Some(MenuAction::Confirm) => match **selection {
+            PauseChoice::Resume => app_state.set(AppState::InGame),
+            PauseChoice::Restart => {
+                commands.insert_resource(RestartRequested);
+                app_state.set(AppState::InGame);
+            }
+            PauseChoice::Quit => app_state.set(AppState::Title),
+        },
+        None => (),
+    }
+
+    for (choice, mut text) in entries_q.iter_mut() {
+        text.sections[0].style.color = if *choice == **selection {
+            Color::GOLD
+        } else {
+            Color::WHITE
+        };
+    }
+}
+
+pub fn clear_restart(mut commands: Commands) {
+    commands.remove_resource::<RestartRequested>();
+}
@@ -1,11 +1,40 @@
 use crate::prelude::*;
 
+//////////////////////////////////////// Plugin ////////////////////////////////////////
+
+pub struct PlayerPlugin;
+
+impl Plugin for PlayerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<UnitChanged>()
+            .add_event::<UnitAttack>()
+            .add_systems(
+                Update,
+                (
+                    move_units,
+                    // Must not see a new Jump state before its velocity is applied
+                    fall_units.before(update_units),
+                    tick_dashes,
+                    cooldown_dashes,
+                    transition_units.before(AsepriteSystems::Animate),
+                    unit_attacks_ape.after(transition_units),
+                    reorient_units_on_sprite_change,
+                    update_units.after(transition_units),
+                )
+                    .run_if(in_state(AppState::InGame)),
+            )
+            .add_systems(Update, reload_units_sprites);
+    }
+}
+
 /////////////////////////////////////// Spawners ///////////////////////////////////////
 
 pub fn spawn_player(
     commands: &mut Commands,
     aseprite_handles: &AsepriteHandles,
     aseprites: &Assets<Aseprite>,
+    player_tuning: &PlayerTuning,
+    eth_tuning: &EthTuning,
 ) {
     let aseprite_handle = aseprite_handles.get(sprites::Paladin::PATH).unwrap();
     let aseprite = aseprites.get(aseprite_handle).unwrap();
@@ -16,7 +45,7 @@ pub fn spawn_player(
         .insert(UnitKind::Player)
         .insert(VisibilityBundle::default())
         .insert(Gravity::default())
-        .insert(DashCooldown::default())
+        .insert(DashCooldown::from_seconds(player_tuning.dash_cooldown))
         .insert(AsepriteBundle {
             texture_atlas: aseprite.atlas().clone(),
             sprite: TextureAtlasSprite::new(animation.current_frame()),
@@ -32,17 +61,16 @@ pub fn spawn_player(
         .insert(UnitState::Stand)
         .insert(UnitCondition::Normal)
         .insert(Orientation::Right)
-        .insert(EthOwned::default());
+        .insert(EthOwned::new(eth_tuning.max));
 }
 
-pub fn spawn_life_hud(commands: &mut Commands, asset_server: &AssetServer) {
-    let (life_hud_x, life_hud_y) = (-557., 244.);
+pub fn spawn_life_hud(commands: &mut Commands, asset_server: &AssetServer, life_chunks: usize) {
     let life_hud = commands
         .spawn(LifeHud)
         .insert(SpriteBundle {
             texture: asset_server.load("heart_icon.png"),
             transform: Transform {
-                translation: Vec3::new(life_hud_x, life_hud_y, 999.),
+                translation: Vec3::new(LifeHud::X, LifeHud::Y, 999.),
                 scale: Vec3::splat(0.13),
                 ..default()
             },
@@ -50,27 +78,32 @@ pub fn spawn_life_hud(commands: &mut Commands, asset_server: &AssetServer) {
         })
         .id();
 
-    let mut chunks = vec![];
-    let mut offset = life_hud_x + 25.;
-    for _ in 0..5 {
-        let chunk = commands
-            .spawn(SpriteBundle {
-                texture: asset_server.load("life_chunk.png"),
-                transform: Transform {
-                    translation: Vec3::new(offset, life_hud_y, 999.),
-                    scale: Vec3::splat(0.15),
-                    ..default()
-                },
-                ..default()
-            })
-            .id();
-        offset += 15.;
-        chunks.push(chunk);
-    }
+    let chunks = (0..life_chunks)
+        .map(|index| spawn_life_chunk(commands, asset_server, index))
+        .collect();
 
     commands.entity(life_hud).insert(LifeChunks(chunks));
 }
 
+/// The chunk at `index` in the life HUD, the first one is next to the heart.
+pub fn spawn_life_chunk(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    index: usize,
+) -> Entity {
+    commands
+        .spawn(SpriteBundle {
+            texture: asset_server.load("life_chunk.png"),
+            transform: Transform {
+                translation: Vec3::new(LifeHud::X + 25. + 15. * index as f32, LifeHud::Y, 999.),
+                scale: Vec3::splat(0.15),
+                ..default()
+            },
+            ..default()
+        })
+        .id()
+}
+
 ////////////////////////////////////// Components //////////////////////////////////////
 
 #[derive(Component)]
@@ -174,10 +207,10 @@ pub enum UnitCondition {
 }
 
 impl UnitCondition {
-    pub fn damages(&self) -> f32 {
+    pub fn damages(&self, tuning: &PlayerTuning) -> f32 {
         match self {
-            Self::Normal => 30.,
-            Self::Upgraded => 300.,
+            Self::Normal => tuning.damages,
+            Self::Upgraded => tuning.upgraded_damages,
         }
     }
 }
@@ -187,21 +220,29 @@ pub struct Gravity {
     pub vy: f32,
 }
 
+impl Gravity {
+    /// Accelerates downward by `gravity` during `dt`, then moves `transform` accordingly.
+    pub fn fall(&mut self, transform: &mut Transform, gravity: f32, dt: f32) {
+        self.vy -= gravity * dt;
+        transform.translation.y += self.vy * dt;
+    }
+}
+
 #[derive(Component, Deref, DerefMut)]
 pub struct DashTimer(pub Timer);
 
-impl Default for DashTimer {
-    fn default() -> Self {
-        DashTimer(Timer::from_seconds(0.3, TimerMode::Once))
+impl DashTimer {
+    pub fn from_seconds(duration: f32) -> Self {
+        DashTimer(Timer::from_seconds(duration, TimerMode::Once))
     }
 }
 
 #[derive(Component, Deref, DerefMut)]
 pub struct DashCooldown(pub Timer);
 
-impl Default for DashCooldown {
-    fn default() -> Self {
-        DashCooldown(Timer::from_seconds(0.25, TimerMode::Once))
+impl DashCooldown {
+    pub fn from_seconds(duration: f32) -> Self {
+        DashCooldown(Timer::from_seconds(duration, TimerMode::Once))
     }
 }
 
@@ -211,6 +252,11 @@ pub struct UnitAttack(pub Entity);
 #[derive(Component)]
 pub struct LifeHud;
 
+impl LifeHud {
+    const X: f32 = -557.;
+    const Y: f32 = 244.;
+}
+
 #[derive(Component, Deref, DerefMut)]
 pub struct LifeChunks(pub Vec<Entity>);
 
@@ -231,6 +277,7 @@ pub fn update_units(
     )>,
     aseprite_handles: Res<AsepriteHandles>,
     aseprites: Res<Assets<Aseprite>>,
+    tuning: Res<PlayerTuning>,
 ) {
     for &UnitChanged {
         unit,
@@ -271,17 +318,21 @@ pub fn update_units(
                     if let UnitState::Dash = *unit_state {
                         commands
                             .entity(unit)
-                            .insert(DashCooldown::default())
+                            .insert(DashCooldown::from_seconds(tuning.dash_cooldown))
                             .remove::<DashTimer>();
                     }
                 }
 
                 UnitState::Jump => {
-                    commands.entity(unit).insert(Gravity { vy: 500. });
+                    commands.entity(unit).insert(Gravity {
+                        vy: tuning.jump_velocity,
+                    });
                 }
 
                 UnitState::Dash => {
-                    commands.entity(unit).insert(DashTimer::default());
+                    commands
+                        .entity(unit)
+                        .insert(DashTimer::from_seconds(tuning.dash_duration));
                 }
 
                 _ => (),
@@ -382,12 +433,16 @@ pub fn transition_units(
     }
 }
 
-pub fn move_units(time: Res<Time>, mut units_q: Query<(&UnitState, &mut Transform, &Movements)>) {
+pub fn move_units(
+    time: Res<Time>,
+    tuning: Res<PlayerTuning>,
+    mut units_q: Query<(&UnitState, &mut Transform, &Movements)>,
+) {
     for (unit_state, mut transform, movements) in units_q.iter_mut() {
         for moving in movements.iter() {
             let velocity = match *unit_state {
-                UnitState::Dash => 600.,
-                _ => 150.,
+                UnitState::Dash => tuning.dash_velocity,
+                _ => tuning.move_velocity,
             };
 
             match moving {
@@ -406,7 +461,7 @@ pub fn move_units(time: Res<Time>, mut units_q: Query<(&UnitState, &mut Transfor
 pub fn unit_attacks_ape(
     mut commands: Commands,
     mut ev_unit_attack: EventReader<UnitAttack>,
-    ape_icon: Res<ApeIconHandle>,
+    tuning: Res<PlayerTuning>,
     units_q: Query<(&Transform, &UnitCondition)>,
     mut apes_q: Query<
         (
@@ -416,8 +471,9 @@ pub fn unit_attacks_ape(
             &ApeWoundWidth,
             &ApeWoundHandle,
             &Flank,
+            Option<&mut ApeInterrupt>,
         ),
-        With<Ape>,
+        (Or<(With<Ape>, With<Boss>)>, Without<ApeEntering>),
     >,
 ) {
     for &UnitAttack(unit) in ev_unit_attack.read() {
@@ -427,17 +483,29 @@ pub fn unit_attacks_ape(
         };
 
         let unit_x = unit_transform.translation.x;
-        for (ape, ape_transform, mut ape_life, ape_wound_width, ape_wound_h, flank) in
-            apes_q.iter_mut()
+        for (
+            ape,
+            ape_transform,
+            mut ape_life,
+            ape_wound_width,
+            ape_wound_h,
+            flank,
+            mut interrupt,
+        ) in apes_q.iter_mut()
         {
             let ape_x = ape_transform.translation.x;
 
             let close_enough = (unit_x - ape_x).abs() < ape_wound_width.0;
             if close_enough {
-                ape_life.decrease_by(unit_condition.damages());
-                let wound_anim =
-                    spawn_ape_damaged_anim(&mut commands, &ape_life, ape_wound_h, &ape_icon, flank);
-                commands.entity(ape).push_children(&[wound_anim]);
+                wound_ape(
+                    &mut commands,
+                    ape,
+                    &mut ape_life,
+                    unit_condition.damages(&tuning),
+                    ape_wound_h,
+                    flank,
+                    interrupt.as_deref_mut(),
+                );
             }
         }
     }
@@ -445,12 +513,12 @@ pub fn unit_attacks_ape(
 
 pub fn fall_units(
     time: Res<Time>,
+    tuning: Res<PlayerTuning>,
     mut ev_unit_changed: EventWriter<UnitChanged>,
     mut units_q: Query<(Entity, &UnitState, &mut Transform, &mut Gravity)>,
 ) {
     for (unit, unit_state, mut transform, mut gravity) in units_q.iter_mut() {
-        gravity.vy -= 1000. * time.delta_seconds();
-        transform.translation.y += gravity.vy * time.delta_seconds();
+        gravity.fall(&mut transform, tuning.gravity, time.delta_seconds());
 
         let floor = -170.;
         if transform.translation.y < floor {
@@ -478,3 +546,39 @@ pub fn cooldown_dashes(time: Res<Time>, mut units_q: Query<&mut DashCooldown>) {
         cooldown.tick(time.delta());
     }
 }
+
+/// Restarts the animations of the units whose aseprite file has been edited.
+pub fn reload_units_sprites(
+    mut ev_aseprite: EventReader<AssetEvent<Aseprite>>,
+    aseprites: Res<Assets<Aseprite>>,
+    mut units_q: Query<(
+        &UnitKind,
+        &UnitState,
+        &UnitCondition,
+        &Handle<Aseprite>,
+        &mut Handle<TextureAtlas>,
+        &mut AsepriteAnimation,
+    )>,
+) {
+    for ev in ev_aseprite.read() {
+        let AssetEvent::Modified { id } = ev else {
+            continue;
+        };
+        let Some(aseprite) = aseprites.get(*id) else {
+            continue;
+        };
+
+        for (unit_kind, &unit_state, &unit_condition, aseprite_h, mut atlas, mut animation) in
+            units_q.iter_mut()
+        {
+            if aseprite_h.id() != *id {
+                continue;
+            }
+            *atlas = aseprite.atlas().clone();
+            *animation = AsepriteAnimation::new(
+                aseprite.info(),
+                unit_kind.anim_tag(unit_state, unit_condition),
+            );
+        }
+    }
+}
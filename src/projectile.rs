@@ -0,0 +1,333 @@
+use crate::prelude::*;
+
+//////////////////////////////////////// Plugin ////////////////////////////////////////
+
+pub struct ProjectilePlugin;
+
+impl Plugin for ProjectilePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                fly_projectiles,
+                projectiles_player_collision.after(fly_projectiles),
+                unit_attacks_projectiles.after(transition_units),
+                projectiles_apes_collision.after(fly_projectiles),
+                fade_shards,
+            )
+                .run_if(in_state(AppState::InGame)),
+        );
+    }
+}
+
+/////////////////////////////////////// Spawners ///////////////////////////////////////
+
+/// Throws a projectile from `start`, it then only follows gravity.
+pub fn spawn_projectile(
+    commands: &mut Commands,
+    ape: Entity,
+    kind: ProjectileKind,
+    start: Vec2,
+    velocity: Vec2,
+) {
+    commands.spawn((
+        ShapeBundle {
+            path: kind.shape().build(),
+            spatial: SpatialBundle {
+                transform: Transform::from_translation(start.extend(15.)),
+                ..default()
+            },
+            ..default()
+        },
+        Fill::color(kind.color()),
+        Stroke::new(Color::BLACK, 2.),
+        kind,
+        Projectile {
+            vx: velocity.x,
+            deflected: None,
+        },
+        Gravity { vy: velocity.y },
+        ApeAttackOwner(ape),
+    ));
+}
+
+/// Breaks a projectile into a few pieces flying away from `position`.
+pub fn spawn_shards(commands: &mut Commands, kind: ProjectileKind, position: Vec2) {
+    for vx in [-120., -40., 40., 120.] {
+        commands.spawn((
+            ShapeBundle {
+                path: GeometryBuilder::new()
+                    .add(&shapes::Rectangle {
+                        extents: Vec2::splat(6.),
+                        origin: shapes::RectangleOrigin::Center,
+                    })
+                    .build(),
+                spatial: SpatialBundle {
+                    transform: Transform::from_translation(position.extend(15.)),
+                    ..default()
+                },
+                ..default()
+            },
+            Fill::color(kind.color()),
+            Shard {
+                vx,
+                timer: Timer::from_seconds(Shard::DURATION, TimerMode::Once),
+            },
+            Gravity { vy: 250. },
+        ));
+    }
+}
+
+////////////////////////////////////// Components //////////////////////////////////////
+
+/// What apes throw, each kind has its favorite.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Component)]
+pub enum ProjectileKind {
+    Banana,
+    Token,
+}
+
+impl ProjectileKind {
+    pub fn thrown_by(kind: ApeKind) -> Self {
+        match kind {
+            ApeKind::Yacht => Self::Token,
+            ApeKind::King | ApeKind::Terminator => Self::Banana,
+        }
+    }
+
+    fn shape(&self) -> GeometryBuilder {
+        match self {
+            Self::Banana => GeometryBuilder::new().add(&shapes::Ellipse {
+                radii: Vec2::new(18., 8.),
+                center: Vec2::ZERO,
+            }),
+            Self::Token => GeometryBuilder::new().add(&shapes::Circle {
+                radius: 12.,
+                center: Vec2::ZERO,
+            }),
+        }
+    }
+
+    fn color(&self) -> Color {
+        match self {
+            Self::Banana => Color::rgb(1., 0.9, 0.2),
+            Self::Token => Color::GOLD,
+        }
+    }
+}
+
+/// Follows a ballistic arc, its vertical velocity is held by [`Gravity`].
+#[derive(Clone, Copy, Component)]
+pub struct Projectile {
+    pub vx: f32,
+    /// Damages dealt to apes once the player hit it back
+    pub deflected: Option<f32>,
+}
+
+impl Projectile {
+    /// Reach of the projectile around its center
+    pub const HALF_WIDTH: f32 = 24.;
+    pub const HALF_HEIGHT: f32 = 50.;
+    /// Vertical velocity of projectiles hit back, to make it to the apes
+    const DEFLECT_LIFT: f32 = 300.;
+}
+
+/// What is left of a broken projectile.
+#[derive(Component)]
+pub struct Shard {
+    pub vx: f32,
+    pub timer: Timer,
+}
+
+impl Shard {
+    const DURATION: f32 = 0.5;
+}
+
+/////////////////////////////////////// Systems ////////////////////////////////////////
+
+pub fn fly_projectiles(
+    time: Res<Time>,
+    tuning: Res<PlayerTuning>,
+    mut commands: Commands,
+    mut projectiles_q: Query<(
+        Entity,
+        &ProjectileKind,
+        &Projectile,
+        &mut Transform,
+        &mut Gravity,
+    )>,
+) {
+    for (id, &kind, projectile, mut transform, mut gravity) in projectiles_q.iter_mut() {
+        transform.translation.x += projectile.vx * time.delta_seconds();
+        gravity.fall(&mut transform, tuning.gravity, time.delta_seconds());
+
+        let position = transform.translation.truncate();
+        if position.x.abs() > GLOBAL_WIDTH / 2. {
+            commands.entity(id).despawn_recursive();
+        } else if position.y < ApeAttackKind::FLOOR {
+            // Shatters on the platform
+            commands.entity(id).despawn_recursive();
+            spawn_shards(
+                &mut commands,
+                kind,
+                Vec2::new(position.x, ApeAttackKind::FLOOR),
+            );
+        }
+    }
+}
+
+pub fn projectiles_player_collision(
+    mut commands: Commands,
+    mut ev_unit_changed: EventWriter<UnitChanged>,
+    projectiles_q: Query<(Entity, &ProjectileKind, &Projectile, &Transform)>,
+    player_q: Query<(Entity, &Transform, &UnitState, &UnitCondition), With<Player>>,
+    mut health_q: Query<&mut LifeChunks, With<LifeHud>>,
+) {
+    let (player, player_transform, &player_state, player_condition) = player_q.single();
+
+    if matches!(player_condition, UnitCondition::Upgraded)
+        || matches!(
+            player_state,
+            UnitState::Wound | UnitState::Die | UnitState::Dash
+        )
+    {
+        return;
+    }
+
+    let player_position = player_transform.translation.truncate();
+    for (id, &kind, projectile, transform) in projectiles_q.iter() {
+        let position = transform.translation.truncate();
+        let distance = (player_position - position).abs();
+        let in_range = distance.x < Projectile::HALF_WIDTH && distance.y < Projectile::HALF_HEIGHT;
+        if projectile.deflected.is_some() || !in_range {
+            continue;
+        }
+
+        wound_player(
+            &mut commands,
+            &mut ev_unit_changed,
+            player,
+            &mut health_q.single_mut(),
+        );
+        commands.entity(id).despawn_recursive();
+        spawn_shards(&mut commands, kind, position);
+        return;
+    }
+}
+
+/// Attacks break the projectiles they catch, or send them back when facing them.
+pub fn unit_attacks_projectiles(
+    mut commands: Commands,
+    mut ev_unit_attack: EventReader<UnitAttack>,
+    player_tuning: Res<PlayerTuning>,
+    tuning: Res<ApeTuning>,
+    units_q: Query<(&Transform, &UnitCondition, &Orientation)>,
+    mut projectiles_q: Query<(
+        Entity,
+        &ProjectileKind,
+        &mut Projectile,
+        &mut Gravity,
+        &Transform,
+    )>,
+) {
+    for &UnitAttack(unit) in ev_unit_attack.read() {
+        let Ok((unit_transform, unit_condition, orientation)) = units_q.get(unit) else {
+            continue;
+        };
+
+        let unit_position = unit_transform.translation.truncate();
+        for (id, &kind, mut projectile, mut gravity, transform) in projectiles_q.iter_mut() {
+            let position = transform.translation.truncate();
+            let distance = (position - unit_position).abs();
+            let caught = distance.x < tuning.attacks.throw_parry_reach
+                && distance.y < Projectile::HALF_HEIGHT;
+            if projectile.deflected.is_some() || !caught {
+                continue;
+            }
+
+            let facing = match orientation {
+                Orientation::Left => -1.,
+                Orientation::Right => 1.,
+            };
+            if (position.x - unit_position.x) * facing >= 0. {
+                projectile.vx = facing * tuning.attacks.throw_deflect_speed;
+                projectile.deflected = Some(unit_condition.damages(&player_tuning));
+                gravity.vy = Projectile::DEFLECT_LIFT;
+            } else {
+                commands.entity(id).despawn_recursive();
+                spawn_shards(&mut commands, kind, position);
+            }
+        }
+    }
+}
+
+pub fn projectiles_apes_collision(
+    mut commands: Commands,
+    projectiles_q: Query<(Entity, &ProjectileKind, &Projectile, &Transform)>,
+    mut apes_q: Query<
+        (
+            Entity,
+            &Transform,
+            &mut ApeLife,
+            &ApeWoundWidth,
+            &ApeWoundHandle,
+            &Flank,
+            Option<&ApeSlot>,
+            Option<&mut ApeInterrupt>,
+        ),
+        (Or<(With<Ape>, With<Boss>)>, Without<ApeEntering>),
+    >,
+) {
+    for (id, &kind, projectile, transform) in projectiles_q.iter() {
+        let Some(damages) = projectile.deflected else {
+            continue;
+        };
+
+        let position = transform.translation.truncate();
+        // Projectiles fly under perched apes, unless sent back up to them
+        let hit = apes_q
+            .iter_mut()
+            .find(|(_, ape_transform, _, wound_width, _, _, slot, _)| {
+                let perch = slot.and_then(|slot| slot.perch);
+                (ape_transform.translation.x - position.x).abs() < wound_width.0 / 2.
+                    && perch.is_none_or(|perch| ApeAttackRange::level_with_perch(perch, position.y))
+            });
+        if let Some((ape, _, mut ape_life, _, wound_h, flank, _, mut interrupt)) = hit {
+            wound_ape(
+                &mut commands,
+                ape,
+                &mut ape_life,
+                damages,
+                wound_h,
+                flank,
+                interrupt.as_deref_mut(),
+            );
+            commands.entity(id).despawn_recursive();
+            spawn_shards(&mut commands, kind, position);
+        }
+    }
+}
+
+pub fn fade_shards(
+    time: Res<Time>,
+    tuning: Res<PlayerTuning>,
+    mut commands: Commands,
+    mut shards_q: Query<(Entity, &mut Shard, &mut Transform, &mut Gravity, &mut Fill)>,
+) {
+    for (id, mut shard, mut transform, mut gravity, mut fill) in shards_q.iter_mut() {
+        transform.translation.x += shard.vx * time.delta_seconds();
+        gravity.fall(&mut transform, tuning.gravity, time.delta_seconds());
+        if transform.translation.y < ApeAttackKind::FLOOR {
+            transform.translation.y = ApeAttackKind::FLOOR;
+            gravity.vy = 0.;
+            shard.vx = 0.;
+        }
+
+        shard.timer.tick(time.delta());
+        if shard.timer.finished() {
+            commands.entity(id).despawn_recursive();
+        } else {
+            fill.color.set_a(shard.timer.percent_left());
+        }
+    }
+}
@@ -0,0 +1,246 @@
+use std::path::PathBuf;
+
+use bevy::ecs::schedule::ExecutorKind;
+use bevy::time::TimeUpdateStrategy;
+
+use crate::prelude::*;
+
+//////////////////////////////////////// Plugin ////////////////////////////////////////
+
+/// Records the player actions of a run to a file, or plays them back in place of the
+/// keyboard and gamepad.
+///
+/// In both modes the `Update` schedule runs single threaded so that the systems order is the
+/// same when recording and when playing back.
+pub enum ReplayPlugin {
+    Record(PathBuf),
+    Playback(PathBuf),
+}
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.edit_schedule(Update, |schedule| {
+            schedule.set_executor_kind(ExecutorKind::SingleThreaded);
+        });
+
+        match self {
+            Self::Record(path) => {
+                app.insert_resource(Recorder {
+                    path: path.clone(),
+                    replay: None,
+                })
+                .add_systems(
+                    Update,
+                    (start_recording, record_actions)
+                        .chain()
+                        .after(read_player_actions)
+                        .run_if(in_state(AppState::InGame)),
+                )
+                .add_systems(OnEnter(AppState::GameOver), save_recording)
+                .add_systems(
+                    Last,
+                    save_recording.run_if(on_event::<bevy::app::AppExit>()),
+                );
+            }
+            Self::Playback(path) => {
+                let replay = std::fs::read(path)
+                    .map_err(ReplayError::Io)
+                    .and_then(|bytes| Replay::from_bytes(&bytes))
+                    .unwrap_or_else(|e| panic!("Could not play {}: {e}", path.display()));
+
+                let first_delta = replay.frames.first().map(|frame| frame.delta);
+                app.insert_resource(GameRng::from_seed(replay.seed))
+                    .insert_resource(TimeUpdateStrategy::ManualDuration(
+                        first_delta.unwrap_or_default(),
+                    ))
+                    .insert_resource(Playback { replay, cursor: 0 })
+                    .add_systems(
+                        Update,
+                        playback_actions
+                            .before(handle_input)
+                            .run_if(resource_exists::<Playback>())
+                            .run_if(in_state(AppState::InGame)),
+                    );
+            }
+        }
+    }
+}
+
+////////////////////////////////////// Components //////////////////////////////////////
+
+/// The seed and per-frame actions of a single run.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Replay {
+    pub seed: u64,
+    pub frames: Vec<ReplayFrame>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplayFrame {
+    /// Game time elapsed during the frame
+    pub delta: Duration,
+    pub actions: PlayerActions,
+}
+
+impl Replay {
+    const MAGIC: &'static [u8; 4] = b"APES";
+    const VERSION: u8 = 1;
+    const HEADER_LEN: usize = 4 + 1 + 8 + 4;
+    const FRAME_LEN: usize = 4 + 2;
+
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            frames: vec![],
+        }
+    }
+
+    /// Header (magic, version, seed, frames count) followed by 6 bytes per frame: the delta
+    /// in nanoseconds, for the frames to last exactly as long, and the packed actions, all
+    /// little endian.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(Self::HEADER_LEN + self.frames.len() * Self::FRAME_LEN);
+        bytes.extend_from_slice(Self::MAGIC);
+        bytes.push(Self::VERSION);
+        bytes.extend_from_slice(&self.seed.to_le_bytes());
+        bytes.extend_from_slice(&(self.frames.len() as u32).to_le_bytes());
+        for frame in &self.frames {
+            let nanos = frame.delta.as_nanos().min(u32::MAX as u128) as u32;
+            bytes.extend_from_slice(&nanos.to_le_bytes());
+            bytes.extend_from_slice(&frame.actions.to_bits().to_le_bytes());
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ReplayError> {
+        if bytes.len() < Self::HEADER_LEN || &bytes[..4] != Self::MAGIC {
+            return Err(ReplayError::NotAReplay);
+        }
+        if bytes[4] != Self::VERSION {
+            return Err(ReplayError::UnsupportedVersion(bytes[4]));
+        }
+
+        let seed = u64::from_le_bytes(bytes[5..13].try_into().unwrap());
+        let count = u32::from_le_bytes(bytes[13..17].try_into().unwrap()) as usize;
+
+        let body = &bytes[Self::HEADER_LEN..];
+        if body.len() != count * Self::FRAME_LEN {
+            return Err(ReplayError::Truncated);
+        }
+
+        let frames = body
+            .chunks_exact(Self::FRAME_LEN)
+            .map(|chunk| ReplayFrame {
+                delta: Duration::from_nanos(
+                    u32::from_le_bytes(chunk[..4].try_into().unwrap()) as u64
+                ),
+                actions: PlayerActions::from_bits(u16::from_le_bytes(
+                    chunk[4..].try_into().unwrap(),
+                )),
+            })
+            .collect();
+
+        Ok(Self { seed, frames })
+    }
+}
+
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(std::io::Error),
+    NotAReplay,
+    UnsupportedVersion(u8),
+    Truncated,
+}
+
+impl std::fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "{e}"),
+            Self::NotAReplay => write!(f, "not a replay file"),
+            Self::UnsupportedVersion(v) => write!(f, "unsupported replay version {v}"),
+            Self::Truncated => write!(f, "truncated replay file"),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+#[derive(Resource)]
+pub struct Recorder {
+    pub path: PathBuf,
+    pub replay: Option<Replay>,
+}
+
+#[derive(Resource)]
+pub struct Playback {
+    pub replay: Replay,
+    pub cursor: usize,
+}
+
+/////////////////////////////////////// Systems ////////////////////////////////////////
+
+/// A new player means a new run, its recording starts from the current seed.
+pub fn start_recording(
+    rng: Res<GameRng>,
+    mut recorder: ResMut<Recorder>,
+    new_player_q: Query<(), Added<Player>>,
+) {
+    if !new_player_q.is_empty() {
+        recorder.replay = Some(Replay::new(rng.seed()));
+    }
+}
+
+pub fn record_actions(
+    time: Res<Time>,
+    actions: Res<PlayerActions>,
+    mut recorder: ResMut<Recorder>,
+) {
+    if let Some(replay) = recorder.replay.as_mut() {
+        replay.frames.push(ReplayFrame {
+            delta: time.delta(),
+            actions: actions.clone(),
+        });
+    }
+}
+
+pub fn save_recording(mut recorder: ResMut<Recorder>) {
+    let Some(replay) = recorder.replay.take() else {
+        return;
+    };
+
+    match std::fs::write(&recorder.path, replay.to_bytes()) {
+        Ok(()) => info!(
+            "Recorded {} frames with seed {} to {}",
+            replay.frames.len(),
+            replay.seed,
+            recorder.path.display()
+        ),
+        Err(e) => error!("Could not write {}: {e}", recorder.path.display()),
+    }
+}
+
+/// Feeds the recorded actions to `handle_input` and makes the next frame last exactly as long
+/// as it did when recording.
+pub fn playback_actions(
+    mut commands: Commands,
+    mut playback: ResMut<Playback>,
+    mut actions: ResMut<PlayerActions>,
+    mut time_strategy: ResMut<TimeUpdateStrategy>,
+) {
+    let Playback { replay, cursor } = &mut *playback;
+
+    let Some(frame) = replay.frames.get(*cursor) else {
+        info!("Replay finished after {} frames", replay.frames.len());
+        *time_strategy = TimeUpdateStrategy::Automatic;
+        *actions = PlayerActions::default();
+        commands.remove_resource::<Playback>();
+        return;
+    };
+
+    *actions = frame.actions.clone();
+    *cursor += 1;
+
+    if let Some(next) = replay.frames.get(*cursor) {
+        *time_strategy = TimeUpdateStrategy::ManualDuration(next.delta);
+    }
+}
@@ -0,0 +1,658 @@
+use crate::prelude::*;
+
+//////////////////////////////////////// Plugin ////////////////////////////////////////
+
+pub struct TuningPlugin;
+
+impl Plugin for TuningPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<Tuning>()
+            .register_asset_loader(TuningLoader)
+            .add_systems(OnEnter(AppState::Loading), load_tuning)
+            .add_systems(
+                Update,
+                reload_tuning.run_if(resource_exists::<TuningHandle>()),
+            )
+            .add_systems(
+                Update,
+                check_tuning
+                    .before(check_assets)
+                    .run_if(in_state(AppState::Loading)),
+            );
+    }
+}
+
+////////////////////////////////////// Components //////////////////////////////////////
+
+/// Every balance value of the game, loaded from `assets/tuning.ron`.
+#[derive(Asset, TypePath, Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Tuning {
+    pub player: PlayerTuning,
+    pub eth: EthTuning,
+    pub ape: ApeTuning,
+    pub wave: WaveTuning,
+    pub boss: BossTuning,
+}
+
+impl Tuning {
+    pub const PATH: &'static str = "tuning.ron";
+
+    pub fn from_ron(bytes: &[u8]) -> Result<Self, TuningError> {
+        let tuning: Self = ron::de::from_bytes(bytes)?;
+        tuning.validate()?;
+        Ok(tuning)
+    }
+
+    pub fn validate(&self) -> Result<(), TuningError> {
+        let Self {
+            player,
+            eth,
+            ape,
+            wave,
+            boss,
+        } = self;
+
+        if player.life_chunks == 0 {
+            return Err(TuningError::invalid(
+                "player.life_chunks",
+                "must be at least 1, got 0",
+            ));
+        }
+        positive("player.move_velocity", player.move_velocity)?;
+        positive("player.dash_velocity", player.dash_velocity)?;
+        positive("player.dash_duration", player.dash_duration)?;
+        positive("player.dash_cooldown", player.dash_cooldown)?;
+        positive("player.gravity", player.gravity)?;
+        positive("player.jump_velocity", player.jump_velocity)?;
+        positive("player.damages", player.damages)?;
+        positive("player.upgraded_damages", player.upgraded_damages)?;
+
+        positive("eth.max", eth.max)?;
+        positive("eth.quantity", eth.quantity)?;
+        positive("eth.decay", eth.decay)?;
+        positive("eth.respawn_delay", eth.respawn_delay)?;
+
+        positive("ape.attack_interval", ape.attack_interval)?;
+        if !(0. ..1.).contains(&ape.attack_jitter) {
+            return Err(TuningError::invalid(
+                "ape.attack_jitter",
+                format!("must be within [0, 1), got {}", ape.attack_jitter),
+            ));
+        }
+        positive("ape.attack_cooldown", ape.attack_cooldown)?;
+        positive("ape.stagger", ape.stagger)?;
+        positive("ape.stun_duration", ape.stun_duration)?;
+        positive("ape.entrance_duration", ape.entrance_duration)?;
+        positive("ape.enrage.patrol_speed", ape.enrage.patrol_speed)?;
+        positive("ape.enrage.laser_charge", ape.enrage.laser_charge)?;
+        if !(0. ..=1.).contains(&ape.enrage.chain_chance) {
+            return Err(TuningError::invalid(
+                "ape.enrage.chain_chance",
+                format!("must be within [0, 1], got {}", ape.enrage.chain_chance),
+            ));
+        }
+        let traits = &ape.traits;
+        for (field, odds) in [
+            ("ape.traits.rare", traits.rare),
+            ("ape.traits.epic", traits.epic),
+            ("ape.traits.legendary", traits.legendary),
+        ] {
+            if !(0. ..=1.).contains(&odds) {
+                return Err(TuningError::invalid(
+                    field,
+                    format!("must be within [0, 1], got {odds}"),
+                ));
+            }
+        }
+        let total = traits.rare + traits.epic + traits.legendary;
+        if total > 1. {
+            return Err(TuningError::invalid(
+                "ape.traits",
+                format!("odds must add up to 1 at most, got {total}"),
+            ));
+        }
+        positive("ape.minions.interval", ape.minions.interval)?;
+        positive("ape.minions.speed", ape.minions.speed)?;
+        positive("ape.minions.eth", ape.minions.eth)?;
+        let attacks = &ape.attacks;
+        positive("ape.attacks.slam_charge", attacks.slam_charge)?;
+        positive("ape.attacks.slam_speed", attacks.slam_speed)?;
+        positive("ape.attacks.slam_duration", attacks.slam_duration)?;
+        positive("ape.attacks.sweep_charge", attacks.sweep_charge)?;
+        positive("ape.attacks.sweep_speed", attacks.sweep_speed)?;
+        positive("ape.attacks.sweep_duration", attacks.sweep_duration)?;
+        positive("ape.attacks.beam_charge", attacks.beam_charge)?;
+        positive("ape.attacks.beam_width", attacks.beam_width)?;
+        positive("ape.attacks.beam_duration", attacks.beam_duration)?;
+        positive("ape.attacks.throw_charge", attacks.throw_charge)?;
+        positive("ape.attacks.throw_flight", attacks.throw_flight)?;
+        positive("ape.attacks.throw_parry_reach", attacks.throw_parry_reach)?;
+        positive(
+            "ape.attacks.throw_deflect_speed",
+            attacks.throw_deflect_speed,
+        )?;
+        let steering = &ape.steering;
+        positive("ape.steering.retreat_distance", steering.retreat_distance)?;
+        positive("ape.steering.rush_speed", steering.rush_speed)?;
+        positive("ape.steering.feint_interval", steering.feint_interval)?;
+        positive("ape.steering.feint_duration", steering.feint_duration)?;
+        if steering.feint_duration >= steering.feint_interval {
+            return Err(TuningError::invalid(
+                "ape.steering.feint_duration",
+                format!(
+                    "must be shorter than the feint interval, got {}",
+                    steering.feint_duration
+                ),
+            ));
+        }
+        if ape.slots.is_empty() {
+            return Err(TuningError::invalid("ape.slots", "must not be empty"));
+        }
+        for (i, slot) in ape.slots.iter().enumerate() {
+            let wall = GLOBAL_WIDTH / 2.;
+            if !(-wall <= slot.min
+                && slot.min <= slot.start
+                && slot.start <= slot.max
+                && slot.max <= wall)
+            {
+                return Err(TuningError::invalid(
+                    format!("ape.slots[{i}]"),
+                    format!("must have -{wall} <= min <= start <= max <= {wall}"),
+                ));
+            }
+            if let Some(perch) = slot.perch {
+                positive(format!("ape.slots[{i}].perch"), perch)?;
+            }
+        }
+        positive("ape.respawn_delay", ape.respawn_delay)?;
+        for kind in ApeKind::ALL {
+            let field = |name| format!("ape.{}.{name}", kind.tuning_key());
+            let stats = ape.kind(kind);
+            positive(field("life"), stats.life)?;
+            positive(field("patrol_speed"), stats.patrol_speed)?;
+            positive(field("interrupt_threshold"), stats.interrupt_threshold)?;
+            if !(0. ..1.).contains(&stats.enrage_below) {
+                return Err(TuningError::invalid(
+                    field("enrage_below"),
+                    format!("must be within [0, 1), got {}", stats.enrage_below),
+                ));
+            }
+            positive(field("laser_reach"), stats.laser_reach)?;
+            positive(field("laser_charge"), stats.laser_charge)?;
+            positive(field("laser_fire"), stats.laser_fire)?;
+            for (i, entry) in stats.loot.iter().enumerate() {
+                let field = |name| format!("ape.{}.loot[{i}].{name}", kind.tuning_key());
+                if !(entry.chance > 0. && entry.chance <= 1.) {
+                    return Err(TuningError::invalid(
+                        field("chance"),
+                        format!("must be within (0, 1], got {}", entry.chance),
+                    ));
+                }
+                if let LootDrop::Eth(quantity) = entry.drop {
+                    positive(field("drop"), quantity)?;
+                }
+            }
+            if stats.attacks.is_empty() {
+                return Err(TuningError::invalid(field("attacks"), "must not be empty"));
+            }
+        }
+
+        if wave.kills == 0 {
+            return Err(TuningError::invalid(
+                "wave.kills",
+                "must be at least 1, got 0",
+            ));
+        }
+        positive("wave.breather", wave.breather)?;
+        positive("wave.banner", wave.banner)?;
+        if wave.first_apes == 0 {
+            return Err(TuningError::invalid(
+                "wave.first_apes",
+                "must be at least 1, got 0",
+            ));
+        }
+        if wave.apes_every == 0 {
+            return Err(TuningError::invalid(
+                "wave.apes_every",
+                "must be at least 1, got 0",
+            ));
+        }
+        if !(wave.life_growth.is_finite() && wave.life_growth >= 0.) {
+            return Err(TuningError::invalid(
+                "wave.life_growth",
+                format!("must not be negative, got {}", wave.life_growth),
+            ));
+        }
+        if !(wave.speed_growth.is_finite() && wave.speed_growth >= 0.) {
+            return Err(TuningError::invalid(
+                "wave.speed_growth",
+                format!("must not be negative, got {}", wave.speed_growth),
+            ));
+        }
+        positive("wave.attack_interval_decay", wave.attack_interval_decay)?;
+        if wave.attack_interval_decay > 1. {
+            return Err(TuningError::invalid(
+                "wave.attack_interval_decay",
+                format!("must be at most 1, got {}", wave.attack_interval_decay),
+            ));
+        }
+        positive("wave.min_attack_interval", wave.min_attack_interval)?;
+
+        if boss.every_kills == 0 {
+            return Err(TuningError::invalid(
+                "boss.every_kills",
+                "must be at least 1, got 0",
+            ));
+        }
+        positive("boss.life", boss.life)?;
+        if boss.phases == 0 {
+            return Err(TuningError::invalid(
+                "boss.phases",
+                "must be at least 1, got 0",
+            ));
+        }
+        positive("boss.patrol_speed", boss.patrol_speed)?;
+        positive("boss.charge_speed", boss.charge_speed)?;
+        positive("boss.attack_interval", boss.attack_interval)?;
+        positive("boss.laser_reach", boss.laser_reach)?;
+        positive("boss.laser_charge", boss.laser_charge)?;
+        positive("boss.laser_fire", boss.laser_fire)?;
+        positive("boss.reward", boss.reward)?;
+
+        Ok(())
+    }
+}
+
+fn positive(field: impl Into<String>, value: f32) -> Result<(), TuningError> {
+    if value.is_finite() && value > 0. {
+        Ok(())
+    } else {
+        Err(TuningError::invalid(
+            field,
+            format!("must be strictly positive, got {value}"),
+        ))
+    }
+}
+
+#[derive(Resource, Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PlayerTuning {
+    pub life_chunks: usize,
+    pub move_velocity: f32,
+    pub dash_velocity: f32,
+    pub dash_duration: f32,
+    pub dash_cooldown: f32,
+    pub gravity: f32,
+    pub jump_velocity: f32,
+    pub damages: f32,
+    pub upgraded_damages: f32,
+}
+
+#[derive(Resource, Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EthTuning {
+    pub max: f32,
+    pub quantity: f32,
+    /// ETH lost per second while upgraded
+    pub decay: f32,
+    pub respawn_delay: f32,
+}
+
+#[derive(Resource, Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ApeTuning {
+    pub attack_interval: f32,
+    /// Random share of the interval added or removed for each attack
+    pub attack_jitter: f32,
+    /// Minimum pause between the end of an attack and the next one of the same ape
+    pub attack_cooldown: f32,
+    pub coordination: ApesCoordination,
+    /// Minimum delay between two apes attacks when staggered
+    pub stagger: f32,
+    /// Time an ape can't move nor attack once its charge got interrupted
+    pub stun_duration: f32,
+    /// Time an ape takes to enter the arena, it can't be hurt nor attack meanwhile
+    pub entrance_duration: f32,
+    pub enrage: ApeEnrageTuning,
+    pub traits: ApeTraitsTuning,
+    pub minions: ApeMinionsTuning,
+    pub attacks: ApeAttacksTuning,
+    pub steering: ApeSteeringTuning,
+    /// Where apes spawn, in the order they get filled as waves bring more apes
+    pub slots: Vec<SpawnSlot>,
+    pub respawn_delay: f32,
+    pub king: ApeKindTuning,
+    pub yacht: ApeKindTuning,
+    pub terminator: ApeKindTuning,
+}
+
+impl ApeTuning {
+    pub fn kind(&self, kind: ApeKind) -> &ApeKindTuning {
+        match kind {
+            ApeKind::King => &self.king,
+            ApeKind::Yacht => &self.yacht,
+            ApeKind::Terminator => &self.terminator,
+        }
+    }
+
+    pub fn kind_mut(&mut self, kind: ApeKind) -> &mut ApeKindTuning {
+        match kind {
+            ApeKind::King => &mut self.king,
+            ApeKind::Yacht => &mut self.yacht,
+            ApeKind::Terminator => &mut self.terminator,
+        }
+    }
+
+    pub fn laser_charge(&self, kind: ApeKind, enraged: bool) -> f32 {
+        let charge = self.kind(kind).laser_charge;
+        if enraged {
+            charge * self.enrage.laser_charge
+        } else {
+            charge
+        }
+    }
+}
+
+/// How much enraged apes change, relative to their regular stats.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ApeEnrageTuning {
+    pub patrol_speed: f32,
+    pub laser_charge: f32,
+    /// Odds of following an attack with another one right away
+    pub chain_chance: f32,
+}
+
+/// Odds of each trait of an ape being of a given rarity, common otherwise.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ApeTraitsTuning {
+    pub rare: f32,
+    pub epic: f32,
+    pub legendary: f32,
+}
+
+/// The small monkeys apes send running at the player.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ApeMinionsTuning {
+    /// Time between two minions of the same ape
+    pub interval: f32,
+    /// Minions running at once, for all apes
+    pub max: usize,
+    pub speed: f32,
+    /// Dropped when killed
+    pub eth: f32,
+}
+
+/// Telegraph durations (`charge`) and shapes of the attacks other than lasers.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ApeAttacksTuning {
+    pub slam_charge: f32,
+    pub slam_speed: f32,
+    pub slam_duration: f32,
+    pub sweep_charge: f32,
+    pub sweep_speed: f32,
+    pub sweep_duration: f32,
+    pub beam_charge: f32,
+    pub beam_width: f32,
+    pub beam_duration: f32,
+    pub throw_charge: f32,
+    /// Time for the projectile to reach its target
+    pub throw_flight: f32,
+    /// Distance from the player where its attacks catch projectiles
+    pub throw_parry_reach: f32,
+    /// Horizontal speed of projectiles hit back at the apes
+    pub throw_deflect_speed: f32,
+}
+
+/// Distances and timings of the apes moving around the player.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ApeSteeringTuning {
+    /// Apes closer than this to the player back off
+    pub retreat_distance: f32,
+    /// Speed when backing off or feinting, relative to the patrol speed
+    pub rush_speed: f32,
+    pub feint_interval: f32,
+    /// Part of the interval spent rushing at the player
+    pub feint_duration: f32,
+}
+
+/// A spot of the arena held by one ape at a time.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SpawnSlot {
+    pub start: f32,
+    /// Patrol bounds
+    pub min: f32,
+    pub max: f32,
+    /// The side the ape fights from, it faces the other one
+    pub flank: Flank,
+    /// Height of the ledge the ape stands on, on the floor otherwise
+    #[serde(default)]
+    pub perch: Option<f32>,
+}
+
+/// How an ape moves on its flank.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum ApeSteering {
+    /// Back and forth, whatever the player does
+    Patrol,
+    /// Keeps the player in the middle of its lasers, backs off when the player comes close
+    Chase,
+    /// Chases, and regularly rushes at the player before backing off
+    Feint,
+}
+
+/// How an ape shows up in the arena.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum ApeEntrance {
+    /// Falls from the top and raises dust when landing
+    Drop,
+    /// Walks in from its side of the arena
+    Slide,
+    /// Shimmers in place
+    Teleport,
+}
+
+/// How apes attacks relate to each other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum ApesCoordination {
+    /// Each ape fires whenever it is ready
+    Independent,
+    /// Apes take turns
+    Alternate,
+    /// All apes fire together, from both sides
+    Pincer,
+    /// Apes fire one at a time, with some delay between them
+    Staggered,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ApeKindTuning {
+    pub life: f32,
+    pub patrol_speed: f32,
+    pub steering: ApeSteering,
+    pub entrance: ApeEntrance,
+    /// Damages taken during the charge of an attack that cancel it
+    pub interrupt_threshold: f32,
+    /// Share of its life under which the ape gets enraged, never when 0
+    pub enrage_below: f32,
+    /// Length of the lasers, relative to the sprite
+    pub laser_reach: f32,
+    /// Duration of the blinking eyes before the lasers
+    pub laser_charge: f32,
+    pub laser_fire: f32,
+    /// What may drop when killed
+    pub loot: Vec<LootEntry>,
+    /// Picked at random for each attack
+    pub attacks: Vec<ApeAttackKind>,
+}
+
+#[derive(Resource, Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WaveTuning {
+    /// Kills needed to clear a wave
+    pub kills: usize,
+    /// Pause without apes between two waves
+    pub breather: f32,
+    /// How long the wave banner stays on screen
+    pub banner: f32,
+    /// Apes fighting at once during the first wave
+    pub first_apes: usize,
+    /// One more ape fighting at once every that many waves
+    pub apes_every: usize,
+    /// Ape life added per wave, relative to its kind life
+    pub life_growth: f32,
+    /// Ape patrol speed added per wave, relative to its kind speed
+    pub speed_growth: f32,
+    /// Factor applied to the attack interval at each wave
+    pub attack_interval_decay: f32,
+    pub min_attack_interval: f32,
+}
+
+#[derive(Resource, Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BossTuning {
+    /// Kills between two boss fights
+    pub every_kills: usize,
+    pub life: f32,
+    /// Each phase unlocks an attack pattern and speeds up the attacks
+    pub phases: usize,
+    pub patrol_speed: f32,
+    pub charge_speed: f32,
+    /// Pause between two attack patterns, during the first phase
+    pub attack_interval: f32,
+    pub laser_reach: f32,
+    pub laser_charge: f32,
+    pub laser_fire: f32,
+    /// ETH dropped when killed, spread in several pieces
+    pub reward: f32,
+}
+
+#[derive(Resource)]
+pub struct TuningHandle(pub Handle<Tuning>);
+
+#[derive(Debug)]
+pub enum TuningError {
+    Io(std::io::Error),
+    Parse(ron::error::SpannedError),
+    Invalid { field: String, reason: String },
+}
+
+impl TuningError {
+    fn invalid(field: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self::Invalid {
+            field: field.into(),
+            reason: reason.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for TuningError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "could not read tuning file: {e}"),
+            Self::Parse(e) => write!(f, "malformed tuning file: {e}"),
+            Self::Invalid { field, reason } => write!(f, "invalid `{field}`: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for TuningError {}
+
+impl From<std::io::Error> for TuningError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<ron::error::SpannedError> for TuningError {
+    fn from(e: ron::error::SpannedError) -> Self {
+        Self::Parse(e)
+    }
+}
+
+#[derive(Default)]
+pub struct TuningLoader;
+
+impl AssetLoader for TuningLoader {
+    type Asset = Tuning;
+    type Settings = ();
+    type Error = TuningError;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Tuning, TuningError>> {
+        Box::pin(async move {
+            let mut bytes = vec![];
+            reader.read_to_end(&mut bytes).await?;
+            Tuning::from_ron(&bytes)
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ron"]
+    }
+}
+
+/////////////////////////////////////// Systems ////////////////////////////////////////
+
+pub fn load_tuning(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(TuningHandle(asset_server.load(Tuning::PATH)));
+}
+
+/// Exposes the loaded tuning as resources, every gameplay system reads its values from them.
+pub fn check_tuning(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    tuning_h: Res<TuningHandle>,
+    tunings: Res<Assets<Tuning>>,
+) {
+    if let Some(LoadState::Failed) = asset_server.get_load_state(tuning_h.0.id()) {
+        panic!(
+            "Could not load {}, see the asset loader error above",
+            Tuning::PATH
+        );
+    }
+
+    if let Some(tuning) = tunings.get(&tuning_h.0) {
+        commands.insert_resource(tuning.player.clone());
+        commands.insert_resource(tuning.eth.clone());
+        commands.insert_resource(tuning.ape.clone());
+        commands.insert_resource(tuning.wave.clone());
+        commands.insert_resource(tuning.boss.clone());
+    }
+}
+
+/// Replaces the tuning resources when `tuning.ron` is edited while the game is running.
+///
+/// An invalid edit is reported by the asset loader and the previous values are kept.
+pub fn reload_tuning(
+    mut commands: Commands,
+    mut ev_tuning: EventReader<AssetEvent<Tuning>>,
+    tuning_h: Res<TuningHandle>,
+    tunings: Res<Assets<Tuning>>,
+) {
+    for ev in ev_tuning.read() {
+        if !ev.is_modified(tuning_h.0.id()) {
+            continue;
+        }
+        let Some(tuning) = tunings.get(&tuning_h.0) else {
+            continue;
+        };
+        info!("Reloaded {}", Tuning::PATH);
+        commands.insert_resource(tuning.player.clone());
+        commands.insert_resource(tuning.eth.clone());
+        commands.insert_resource(tuning.ape.clone());
+        commands.insert_resource(tuning.wave.clone());
+        commands.insert_resource(tuning.boss.clone());
+    }
+}
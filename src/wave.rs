@@ -0,0 +1,177 @@
+use crate::prelude::*;
+
+//////////////////////////////////////// Plugin ////////////////////////////////////////
+
+pub struct WavePlugin;
+
+impl Plugin for WavePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Wave>()
+            .add_event::<WaveChanged>()
+            .add_systems(
+                Update,
+                (advance_wave, show_wave_banner.after(advance_wave))
+                    .run_if(in_state(AppState::InGame)),
+            )
+            .add_systems(OnExit(AppState::InGame), hide_wave_banner);
+    }
+}
+
+/////////////////////////////////////// Spawners ///////////////////////////////////////
+
+pub fn spawn_wave_banner(commands: &mut Commands, font_handle: &Handle<Font>) {
+    commands
+        .spawn(Text2dBundle {
+            text: Text::from_section(
+                "",
+                TextStyle {
+                    font: font_handle.clone(),
+                    font_size: 60.0,
+                    color: Color::GOLD,
+                },
+            )
+            .with_alignment(TextAlignment::Center),
+            visibility: Visibility::Hidden,
+            transform: Transform::from_xyz(0., 120., 998.),
+            ..default()
+        })
+        .insert(WaveBanner(Timer::new(Duration::ZERO, TimerMode::Once)));
+}
+
+////////////////////////////////////// Components //////////////////////////////////////
+
+/// Progression of the current run, each wave needs a few kills and makes apes tougher.
+#[derive(Resource, Debug, Clone)]
+pub struct Wave {
+    /// Starts at 1
+    pub number: usize,
+    /// Score when the wave started
+    pub kills_at_start: usize,
+    pub phase: WavePhase,
+}
+
+impl Default for Wave {
+    fn default() -> Self {
+        Self {
+            number: 1,
+            kills_at_start: 0,
+            phase: WavePhase::Fighting,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum WavePhase {
+    Fighting,
+    /// Enough kills, waiting for the remaining apes to die
+    Clearing,
+    Breather(Timer),
+}
+
+impl Wave {
+    fn level(&self) -> f32 {
+        (self.number - 1) as f32
+    }
+
+    pub fn spawns_apes(&self) -> bool {
+        matches!(self.phase, WavePhase::Fighting)
+    }
+
+    /// The spawn slots held by apes during this wave, out of the `available` ones.
+    pub fn slots(&self, tuning: &WaveTuning, available: usize) -> std::ops::Range<usize> {
+        let count = tuning.first_apes + (self.number - 1) / tuning.apes_every;
+        0..count.min(available)
+    }
+
+    pub fn life_scale(&self, tuning: &WaveTuning) -> f32 {
+        1. + tuning.life_growth * self.level()
+    }
+
+    pub fn speed_scale(&self, tuning: &WaveTuning) -> f32 {
+        1. + tuning.speed_growth * self.level()
+    }
+
+    pub fn attack_interval(&self, tuning: &WaveTuning, base: f32) -> f32 {
+        let interval = base * tuning.attack_interval_decay.powf(self.level());
+        interval.max(tuning.min_attack_interval)
+    }
+}
+
+#[derive(Event)]
+pub enum WaveChanged {
+    Started(usize),
+    Cleared(usize),
+}
+
+/// Shows the wave changes for a little while.
+#[derive(Component, Deref, DerefMut)]
+pub struct WaveBanner(Timer);
+
+/////////////////////////////////////// Systems ////////////////////////////////////////
+
+pub fn advance_wave(
+    time: Res<Time>,
+    score: Res<Score>,
+    tuning: Res<WaveTuning>,
+    mut wave: ResMut<Wave>,
+    mut ev_wave_changed: EventWriter<WaveChanged>,
+    apes_q: Query<(), With<Ape>>,
+) {
+    let wave = &mut *wave;
+    match &mut wave.phase {
+        WavePhase::Fighting => {
+            if score.0 - wave.kills_at_start >= tuning.kills {
+                wave.phase = WavePhase::Clearing;
+            }
+        }
+        WavePhase::Clearing => {
+            if apes_q.is_empty() {
+                wave.phase =
+                    WavePhase::Breather(Timer::from_seconds(tuning.breather, TimerMode::Once));
+                ev_wave_changed.send(WaveChanged::Cleared(wave.number));
+            }
+        }
+        WavePhase::Breather(timer) => {
+            if timer.tick(time.delta()).finished() {
+                wave.number += 1;
+                wave.kills_at_start = score.0;
+                wave.phase = WavePhase::Fighting;
+                ev_wave_changed.send(WaveChanged::Started(wave.number));
+            }
+        }
+    }
+}
+
+pub fn show_wave_banner(
+    time: Res<Time>,
+    wave: Res<Wave>,
+    tuning: Res<WaveTuning>,
+    mut ev_wave_changed: EventReader<WaveChanged>,
+    new_player_q: Query<(), Added<Player>>,
+    mut banner_q: Query<(&mut Text, &mut Visibility, &mut WaveBanner)>,
+) {
+    let (mut text, mut visibility, mut banner) = banner_q.single_mut();
+
+    // A new player means a new run
+    let first_wave = (!new_player_q.is_empty()).then_some(WaveChanged::Started(wave.number));
+
+    for ev in ev_wave_changed.read().chain(first_wave.as_ref()) {
+        text.sections[0].value = match ev {
+            WaveChanged::Started(number) => format!("Wave   {number}"),
+            WaveChanged::Cleared(number) => format!("Wave   {number}   cleared"),
+        };
+        banner.set_duration(Duration::from_secs_f32(tuning.banner));
+        banner.reset();
+    }
+
+    banner.tick(time.delta());
+    *visibility = if banner.finished() {
+        Visibility::Hidden
+    } else {
+        Visibility::Visible
+    };
+}
+
+pub fn hide_wave_banner(mut banner_q: Query<&mut Visibility, With<WaveBanner>>) {
+    *banner_q.single_mut() = Visibility::Hidden;
+}
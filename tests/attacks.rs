@@ -0,0 +1,147 @@
+mod common;
+
+use angry_apes::prelude::*;
+use common::TestGame;
+
+/// A game whose apes only attack when told to.
+fn quiet_game() -> TestGame {
+    let mut game = TestGame::new();
+    game.hold_ape_attacks();
+    game
+}
+
+#[test]
+fn slam_wounds_grounded_player() {
+    let mut game = quiet_game();
+    let chunks = game.life_chunks();
+    let (ape_x, _) = game.ape(Flank::Left).unwrap();
+    game.place_player(ape_x + 200.);
+
+    game.start_ape_attack(Flank::Left, ApeAttackKind::Slam);
+    let wounded = game.run_until(180, |game| game.life_chunks() < chunks);
+    assert!(wounded, "shockwave missed the player");
+}
+
+#[test]
+fn slam_is_jumped_over() {
+    let mut game = quiet_game();
+    let chunks = game.life_chunks();
+    let tuning = game.ape_tuning().attacks;
+    let (ape_x, _) = game.ape(Flank::Left).unwrap();
+    game.place_player(ape_x + 200.);
+
+    game.start_ape_attack(Flank::Left, ApeAttackKind::Slam);
+    // Airborne when the shockwave rolls by
+    let arrival = tuning.slam_charge + 200. / tuning.slam_speed;
+    game.seconds(arrival - 0.3);
+    game.tap(PlayerInput::JUMP);
+    game.seconds(tuning.slam_duration);
+    assert_eq!(game.life_chunks(), chunks);
+}
+
+#[test]
+fn beam_falls_where_the_player_stood() {
+    let mut game = quiet_game();
+    let chunks = game.life_chunks();
+    let tuning = game.ape_tuning().attacks;
+    game.place_player(0.);
+
+    game.start_ape_attack(Flank::Left, ApeAttackKind::Beam);
+    game.seconds(tuning.beam_charge / 2.);
+    // Jumping does not help against a beam from above
+    game.tap(PlayerInput::JUMP);
+
+    let wounded = game.run_until(120, |game| game.life_chunks() < chunks);
+    assert!(wounded, "beam missed the player");
+}
+
+#[test]
+fn thrown_projectile_lands_on_the_player() {
+    let mut game = quiet_game();
+    let chunks = game.life_chunks();
+    let (ape_x, _) = game.ape(Flank::Left).unwrap();
+    game.place_player(ape_x + 300.);
+
+    game.start_ape_attack(Flank::Left, ApeAttackKind::Throw);
+    let wounded = game.run_until(180, |game| game.life_chunks() < chunks);
+    assert!(wounded, "projectile missed the player");
+}
+
+#[test]
+fn missed_projectile_shatters_on_the_platform() {
+    let mut game = quiet_game();
+    let chunks = game.life_chunks();
+    let tuning = game.ape_tuning().attacks;
+    let (ape_x, _) = game.ape(Flank::Left).unwrap();
+    game.place_player(ape_x + 300.);
+
+    game.start_ape_attack(Flank::Left, ApeAttackKind::Throw);
+    game.seconds(tuning.throw_charge + 0.1);
+    assert_eq!(game.projectiles().len(), 1);
+    // Out of the way before it lands
+    game.place_player(ape_x + 600.);
+
+    let shattered = game.run_until(120, |game| game.projectiles().is_empty());
+    assert!(shattered, "projectile never landed");
+    assert!(game.shards_count() > 0);
+    assert_eq!(game.life_chunks(), chunks);
+}
+
+#[test]
+fn attacked_projectile_is_sent_back_to_the_ape() {
+    let mut game = quiet_game();
+    let chunks = game.life_chunks();
+    let (ape_x, ape_life) = game.ape(Flank::Left).unwrap();
+    game.place_player(ape_x + 300.);
+    // Facing the ape
+    game.tap(PlayerInput::LEFT);
+    let player_x = game.player_translation().x;
+
+    // Still within reach when the swing lands
+    game.throw_projectile(
+        Flank::Left,
+        Vec2::new(player_x - 60., -150.),
+        Vec2::new(0., 250.),
+    );
+    game.tap(PlayerInput::ATTACK);
+
+    let deflected = game.run_until(60, |game| {
+        game.projectiles().iter().any(|&(_, deflected)| deflected)
+    });
+    assert!(deflected, "attack missed the projectile");
+
+    let ape_hit = game.run_until(120, |game| {
+        game.ape(Flank::Left)
+            .is_none_or(|(_, life)| life.current < ape_life.current)
+    });
+    assert!(ape_hit, "projectile missed the ape");
+    assert_eq!(game.life_chunks(), chunks);
+}
+
+#[test]
+fn projectile_sent_back_flies_under_a_perched_ape() {
+    let mut game = quiet_game();
+    game.perch_ape(Flank::Left, 160.);
+    let (ape_x, ape_life) = game.ape(Flank::Left).unwrap();
+    game.place_player(ape_x + 300.);
+    // Facing the ape
+    game.tap(PlayerInput::LEFT);
+    let player_x = game.player_translation().x;
+
+    game.throw_projectile(
+        Flank::Left,
+        Vec2::new(player_x - 60., -150.),
+        Vec2::new(0., 250.),
+    );
+    game.tap(PlayerInput::ATTACK);
+
+    let deflected = game.run_until(60, |game| {
+        game.projectiles().iter().any(|&(_, deflected)| deflected)
+    });
+    assert!(deflected, "attack missed the projectile");
+
+    let gone = game.run_until(120, |game| game.projectiles().is_empty());
+    assert!(gone, "projectile never left");
+    let (_, life) = game.ape(Flank::Left).unwrap();
+    assert_eq!(life.current, ape_life.current);
+}
@@ -0,0 +1,56 @@
+mod common;
+
+use angry_apes::prelude::*;
+use common::TestGame;
+
+/// Runs the game until the boss is due and has entered the arena.
+fn summon_boss(game: &mut TestGame) {
+    let tuning = game.boss_tuning();
+    game.set_score(tuning.every_kills - 1);
+    // The boss waits for the apes left to be killed
+    for flank in [Flank::Left, Flank::Right] {
+        assert!(game.kill_ape(flank), "ape was not killed");
+    }
+
+    let summoned = game.run_until(60, |game| game.boss().is_some());
+    assert!(summoned, "boss never showed up");
+}
+
+#[test]
+fn boss_takes_over_the_arena() {
+    let mut game = TestGame::new();
+    summon_boss(&mut game);
+
+    let (_, life) = game.boss().unwrap();
+    assert_eq!(life.max, game.boss_tuning().life);
+
+    // Regular apes stay away while the boss is alive
+    game.seconds(game.ape_tuning().respawn_delay + 1.);
+    assert!(game.ape_lives().is_empty());
+}
+
+#[test]
+fn killed_boss_drops_a_big_reward() {
+    let mut game = TestGame::new();
+    summon_boss(&mut game);
+    let lying_eth: f32 = game.eth_quantities().iter().sum();
+
+    game.set_boss_life(1.);
+    game.tap(PlayerInput::ATTACK);
+    let hit = game.run_until(120, |game| {
+        let (boss_x, life) = game.boss().unwrap();
+        game.place_player(boss_x);
+        life.current == 0.
+    });
+    assert!(hit, "boss was not hit");
+
+    // Out of the way of the loot
+    let (boss_x, _) = game.boss().unwrap();
+    game.place_player(if boss_x > 0. { -540. } else { 540. });
+    let killed = game.run_until(60, |game| game.boss().is_none());
+    assert!(killed, "boss was not killed");
+
+    let dropped = game.eth_quantities().iter().sum::<f32>() - lying_eth;
+    assert!((dropped - game.boss_tuning().reward).abs() < 1e-3);
+    assert_eq!(game.score(), game.boss_tuning().every_kills + 2);
+}
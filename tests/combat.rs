@@ -0,0 +1,334 @@
+mod common;
+
+use angry_apes::prelude::*;
+use common::TestGame;
+
+/// Distance from an ape on the left flank to the middle of its lasers.
+const LEFT_LASER_DISTANCE: f32 = 312.;
+
+#[test]
+fn attack_damages_ape_in_range() {
+    let mut game = TestGame::new();
+    let (ape_x, life) = game.ape(Flank::Left).unwrap();
+    assert_eq!(life.current, life.max);
+
+    game.place_player(ape_x);
+    game.tap(PlayerInput::ATTACK);
+    game.frame();
+    assert_eq!(game.player_state(), UnitState::Attack);
+
+    let damaged = game.run_until(120, |game| {
+        let (ape_x, life) = game.ape(Flank::Left).unwrap();
+        game.place_player(ape_x);
+        life.current < life.max
+    });
+    assert!(damaged, "ape was not damaged");
+
+    let (_, right_life) = game.ape(Flank::Right).unwrap();
+    assert_eq!(right_life.current, right_life.max);
+}
+
+#[test]
+fn attack_misses_ape_out_of_range() {
+    let mut game = TestGame::new();
+    let (ape_x, _) = game.ape(Flank::Left).unwrap();
+
+    game.place_player(ape_x + 250.);
+    game.tap(PlayerInput::ATTACK);
+    game.frame();
+    assert_eq!(game.player_state(), UnitState::Attack);
+
+    game.run_until(120, |game| game.player_state() == UnitState::Stand);
+
+    assert!(game.ape_lives().iter().all(|life| life.current == life.max));
+}
+
+#[test]
+fn ape_life_depends_on_its_kind() {
+    let mut game = TestGame::new();
+    let tuning = game.ape_tuning();
+
+    for flank in [Flank::Left, Flank::Right] {
+        let kind = game.ape_kind(flank).unwrap();
+        let traits = game.ape_traits(flank).unwrap();
+        let (_, life) = game.ape(flank).unwrap();
+        assert_eq!(life.max, tuning.kind(kind).life * traits.life_scale());
+    }
+}
+
+#[test]
+fn killed_ape_drops_its_sure_loot() {
+    let mut game = TestGame::new();
+    game.tune_apes(|ape| {
+        ape.loot = vec![LootEntry {
+            drop: LootDrop::Eth(12.),
+            chance: 1.,
+        }]
+    });
+    // Boosted by the hat of the ape
+    let eth_scale = game.ape_traits(Flank::Left).unwrap().eth_scale();
+
+    assert!(game.kill_ape(Flank::Left), "ape was not killed");
+    assert_eq!(game.score(), 1);
+    assert!(game.eth_quantities().contains(&(12. * eth_scale)));
+}
+
+#[test]
+fn laser_wounds_player() {
+    let mut game = TestGame::new();
+    let chunks = game.life_chunks();
+
+    let wounded = game.run_until(600, |game| {
+        let (ape_x, _) = game.ape(Flank::Left).unwrap();
+        game.place_player(ape_x + LEFT_LASER_DISTANCE);
+        game.life_chunks() < chunks
+    });
+    assert!(wounded, "player was never hit");
+
+    game.frame();
+    assert_eq!(game.player_state(), UnitState::Wound);
+    assert_eq!(game.app_state(), AppState::InGame);
+}
+
+#[test]
+fn perched_ape_lasers_only_hit_a_jumping_player() {
+    let mut game = TestGame::new();
+    let chunks = game.life_chunks();
+    game.hold_ape_attacks();
+    game.set_ape_tuning(|tuning| tuning.minions.interval = 1000.);
+    game.perch_ape(Flank::Left, 160.);
+    let charge = game.ape_laser_charge(Flank::Left).unwrap().as_secs_f32();
+    let under_lasers = |game: &mut TestGame| {
+        let (ape_x, _) = game.ape(Flank::Left).unwrap();
+        game.place_player(ape_x + LEFT_LASER_DISTANCE);
+    };
+
+    // The lasers fly over the player standing on the floor
+    game.start_ape_attack(Flank::Left, ApeAttackKind::Lasers);
+    let over = game.run_until(600, |game| {
+        under_lasers(game);
+        !game.ape_attacking(Flank::Left)
+    });
+    assert!(over, "lasers never stopped");
+    assert_eq!(game.life_chunks(), chunks);
+
+    // Jumping leads right into them
+    game.start_ape_attack(Flank::Left, ApeAttackKind::Lasers);
+    game.run_until(
+        (charge / TestGame::FRAME.as_secs_f32()) as usize + 1,
+        |game| {
+            under_lasers(game);
+            false
+        },
+    );
+    game.tap(PlayerInput::JUMP);
+    let wounded = game.run_until(60, |game| {
+        under_lasers(game);
+        game.life_chunks() < chunks
+    });
+    assert!(wounded, "lasers missed the jumping player");
+}
+
+#[test]
+fn losing_last_life_chunk_ends_the_game() {
+    let mut game = TestGame::new();
+    game.truncate_life_chunks(1);
+
+    let over = game.run_until(1200, |game| {
+        if let Some((ape_x, _)) = game.ape(Flank::Left) {
+            game.place_player(ape_x + LEFT_LASER_DISTANCE);
+        }
+        game.app_state() == AppState::GameOver
+    });
+    assert!(over, "game never ended");
+}
+
+#[test]
+fn apes_attacks_are_staggered() {
+    let mut game = TestGame::new();
+    let tuning = game.ape_tuning();
+    assert_eq!(tuning.coordination, ApesCoordination::Staggered);
+
+    // Two apes fight at once from the first wave
+    assert_eq!(game.ape_lives().len(), 2);
+
+    // Upgraded players are immune to lasers
+    let eth_max = game.eth_tuning().max;
+
+    let mut charging = vec![];
+    let mut starts = vec![];
+    for frame in 0..1200 {
+        let now_charging = game.charging_apes();
+        for ape in &now_charging {
+            if !charging.contains(ape) {
+                starts.push((frame, *ape));
+            }
+        }
+        charging = now_charging;
+        if frame % 300 == 0 {
+            let player_x = game.player_translation().x;
+            game.spawn_eth(player_x, eth_max);
+        }
+        game.frame();
+    }
+
+    let attackers = starts.iter().map(|&(_, ape)| ape).collect::<HashSet<_>>();
+    assert_eq!(attackers.len(), 2, "both apes should attack");
+
+    let min_gap = (tuning.stagger / TestGame::FRAME.as_secs_f32()).floor() as usize;
+    for pair in starts.windows(2) {
+        assert!(pair[1].0 - pair[0].0 >= min_gap, "apes attacked together");
+    }
+}
+
+/// Hits the ape on `flank` while it charges its lasers.
+fn hit_charging_ape(game: &mut TestGame, flank: Flank) {
+    game.hold_ape_attacks();
+    game.tune_apes(|ape| ape.steering = ApeSteering::Patrol);
+    let (ape_x, _) = game.ape(flank).unwrap();
+    game.place_player(ape_x);
+
+    // The swing lands at the end of the attack animation
+    game.tap(PlayerInput::ATTACK);
+    game.seconds(0.3);
+    game.start_ape_attack(flank, ApeAttackKind::Lasers);
+}
+
+#[test]
+fn enough_damages_during_charge_stun_the_ape() {
+    let mut game = TestGame::new();
+    let chunks = game.life_chunks();
+    game.tune_apes(|ape| ape.interrupt_threshold = 1.);
+
+    hit_charging_ape(&mut game, Flank::Left);
+    let stunned = game.run_until(60, |game| game.ape_stunned(Flank::Left));
+    assert!(stunned, "ape was not stunned");
+    assert!(game.charging_apes().is_empty());
+
+    game.seconds(1.);
+    assert_eq!(game.life_chunks(), chunks);
+    let recovered = game.run_until(120, |game| !game.ape_stunned(Flank::Left));
+    assert!(recovered, "ape never recovered");
+}
+
+#[test]
+fn weak_hits_do_not_interrupt_the_charge() {
+    let mut game = TestGame::new();
+    game.tune_apes(|ape| ape.interrupt_threshold = 10_000.);
+
+    hit_charging_ape(&mut game, Flank::Left);
+    let (_, life) = game.ape(Flank::Left).unwrap();
+    let hit = game.run_until(60, |game| {
+        game.ape(Flank::Left).unwrap().1.current < life.current
+    });
+    assert!(hit, "ape was not hit");
+    assert!(!game.ape_stunned(Flank::Left));
+    assert_eq!(game.charging_apes().len(), 1);
+}
+
+#[test]
+fn wounded_ape_gets_enraged_below_its_threshold() {
+    let mut game = TestGame::new();
+    let kind = game.ape_kind(Flank::Left).unwrap();
+    let tuning = game.ape_tuning();
+    let stats = tuning.kind(kind);
+    let charge = game.ape_laser_charge(Flank::Left).unwrap();
+
+    let (_, life) = game.ape(Flank::Left).unwrap();
+    game.set_ape_life(Flank::Left, life.max * (stats.enrage_below + 0.05));
+    game.frame();
+    assert!(!game.ape_enraged(Flank::Left));
+
+    game.set_ape_life(Flank::Left, life.max * (stats.enrage_below - 0.05));
+    game.frame();
+    assert!(game.ape_enraged(Flank::Left), "ape was not enraged");
+    assert!(game.ape_laser_charge(Flank::Left).unwrap() < charge);
+}
+
+#[test]
+fn enraged_ape_may_chain_its_attacks() {
+    let mut game = TestGame::new();
+    game.set_ape_tuning(|tuning| tuning.enrage.chain_chance = 1.);
+    game.tune_apes(|ape| ape.steering = ApeSteering::Patrol);
+    game.frame();
+
+    game.set_ape_life(Flank::Left, 1.);
+    game.frame();
+    assert!(game.ape_enraged(Flank::Left));
+
+    let chaining = game.run_until(600, |game| game.ape_chaining(Flank::Left));
+    assert!(chaining, "ape never chained an attack");
+    assert!(game.ape_attacking(Flank::Left));
+
+    // The chained attack starts as soon as the first one is over
+    let over = game.run_until(600, |game| !game.ape_attacking(Flank::Left));
+    assert!(over, "first attack never ended");
+    assert!(game.ape_chaining(Flank::Left));
+    game.frame();
+    assert!(game.ape_attacking(Flank::Left));
+    assert!(!game.ape_chaining(Flank::Left));
+}
+
+#[test]
+fn pincer_apes_charge_one_attack_at_once_when_chaining() {
+    let mut game = TestGame::new();
+    // Apes attack again as soon as they can, all with the same attack so they end together
+    game.set_ape_tuning(|tuning| {
+        tuning.coordination = ApesCoordination::Pincer;
+        tuning.enrage.chain_chance = 1.;
+        tuning.attack_interval = 0.01;
+        tuning.attack_jitter = 0.;
+        tuning.attack_cooldown = 0.;
+    });
+    game.set_wave_tuning(|tuning| tuning.min_attack_interval = 0.01);
+    game.tune_apes(|ape| {
+        ape.steering = ApeSteering::Patrol;
+        ape.attacks = vec![ApeAttackKind::Slam];
+    });
+    game.frame();
+
+    // Only the left ape chains, the right one is ready when its chained attack starts
+    game.set_ape_life(Flank::Left, 1.);
+    game.frame();
+    assert!(game.ape_enraged(Flank::Left));
+
+    // Upgraded players are immune to attacks
+    let eth_max = game.eth_tuning().max;
+    let mut chained = false;
+    for frame in 0..1200 {
+        if frame % 300 == 0 {
+            let player_x = game.player_translation().x;
+            game.spawn_eth(player_x, eth_max);
+        }
+        chained |= game.ape_chaining(Flank::Left);
+        game.frame();
+        let charging = game.charging_apes();
+        let apes = charging.iter().collect::<HashSet<_>>();
+        assert_eq!(apes.len(), charging.len(), "an ape charged two attacks");
+    }
+    assert!(chained, "ape never chained an attack");
+}
+
+#[test]
+fn health_bar_trails_behind_recent_damages() {
+    let mut game = TestGame::new();
+    assert_eq!(game.ape_health_bar(Flank::Left), Some((1., 1.)));
+
+    let (_, life) = game.ape(Flank::Left).unwrap();
+    game.set_ape_life(Flank::Left, life.max / 2.);
+    game.frames(2);
+    assert_eq!(game.ape_health_bar(Flank::Left), Some((0.5, 1.)));
+
+    game.seconds(2.);
+    assert_eq!(game.ape_health_bar(Flank::Left), Some((0.5, 0.5)));
+}
+
+#[test]
+fn nameplate_shows_ape_kind_and_wave() {
+    let mut game = TestGame::new();
+    let kind = game.ape_kind(Flank::Left).unwrap();
+
+    let nameplate = game.ape_nameplate(Flank::Left).unwrap();
+    assert!(nameplate.contains(kind.title()), "{nameplate}");
+    assert!(nameplate.contains("Wave 1"), "{nameplate}");
+}
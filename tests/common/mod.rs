@@ -0,0 +1,730 @@
+#![allow(dead_code)]
+
+use angry_apes::prelude::*;
+use bevy::ecs::system::RunSystemOnce;
+use bevy::input::gamepad::{GamepadButtonChangedEvent, GamepadInfo};
+use bevy::input::keyboard::KeyboardInput;
+use bevy::input::ButtonState;
+use bevy::time::TimeUpdateStrategy;
+
+/// The whole game running headlessly, one fixed-length frame per update.
+///
+/// Inputs are injected as the raw events bevy's input plugin expects from a window or a
+/// gamepad, so they go through the exact same path as in the real game.
+pub struct TestGame {
+    pub app: App,
+}
+
+impl TestGame {
+    pub const SEED: u64 = 42;
+    pub const FRAME: Duration = Duration::from_nanos(1_000_000_000 / 60);
+
+    /// Builds the game and runs it until the first fight has started.
+    pub fn new() -> Self {
+        Self::start(None)
+    }
+
+    /// Like [`TestGame::new`], recording the run or playing back a recorded one.
+    pub fn with_replay(replay: ReplayPlugin) -> Self {
+        Self::start(Some(replay))
+    }
+
+    fn start(replay: Option<ReplayPlugin>) -> Self {
+        let mut app = App::new();
+        app.insert_resource(GameRng::from_seed(Self::SEED))
+            .insert_resource(HighScores::default())
+            .add_plugins((HeadlessPlugin, AngryApesPlugin))
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Self::FRAME));
+        if let Some(replay) = replay {
+            app.add_plugins(replay);
+        }
+
+        // Done by `App::run` otherwise, the image loader is only registered there
+        app.finish();
+        app.cleanup();
+
+        let mut game = Self { app };
+
+        let started = std::time::Instant::now();
+        while game.app_state() != AppState::InGame || !game.has_player() {
+            assert!(
+                started.elapsed() < std::time::Duration::from_secs(30),
+                "Assets took too long to load"
+            );
+            std::thread::sleep(std::time::Duration::from_millis(1));
+            game.app.update();
+        }
+
+        // The first apes are only ready to fight once entered
+        let entered = game.run_until(120, |game| !game.ape_entering(None));
+        assert!(entered, "Apes took too long to enter");
+
+        game
+    }
+
+    //////////////////////////////////////// Frames ////////////////////////////////////////
+
+    pub fn frame(&mut self) {
+        self.app.update();
+    }
+
+    pub fn frames(&mut self, count: usize) {
+        for _ in 0..count {
+            self.frame();
+        }
+    }
+
+    pub fn seconds(&mut self, seconds: f32) {
+        self.frames((seconds / Self::FRAME.as_secs_f32()).ceil() as usize);
+    }
+
+    /// Runs frames until `done` holds, returns whether it did within `max_frames`.
+    pub fn run_until(
+        &mut self,
+        max_frames: usize,
+        mut done: impl FnMut(&mut Self) -> bool,
+    ) -> bool {
+        for _ in 0..max_frames {
+            if done(self) {
+                return true;
+            }
+            self.frame();
+        }
+        done(self)
+    }
+
+    //////////////////////////////////////// Inputs ////////////////////////////////////////
+
+    pub fn press(&mut self, key: KeyCode) {
+        self.send_key(key, ButtonState::Pressed);
+    }
+
+    pub fn release(&mut self, key: KeyCode) {
+        self.send_key(key, ButtonState::Released);
+    }
+
+    /// Presses `key` during one frame, then releases it.
+    pub fn tap(&mut self, key: KeyCode) {
+        self.press(key);
+        self.frame();
+        self.release(key);
+    }
+
+    fn send_key(&mut self, key: KeyCode, state: ButtonState) {
+        self.app.world.send_event(KeyboardInput {
+            scan_code: 0,
+            key_code: Some(key),
+            state,
+            window: Entity::PLACEHOLDER,
+        });
+    }
+
+    pub fn gamepad() -> Gamepad {
+        Gamepad::new(0)
+    }
+
+    /// Plugs the first gamepad, which makes the game switch to gamepad controls.
+    pub fn connect_gamepad(&mut self) {
+        self.app
+            .world
+            .send_event(GamepadEvent::Connection(GamepadConnectionEvent::new(
+                Self::gamepad(),
+                GamepadConnection::Connected(GamepadInfo {
+                    name: "Test gamepad".to_string(),
+                }),
+            )));
+        self.frame();
+    }
+
+    pub fn press_button(&mut self, button: GamepadButtonType) {
+        self.send_button(button, 1.);
+    }
+
+    pub fn release_button(&mut self, button: GamepadButtonType) {
+        self.send_button(button, 0.);
+    }
+
+    /// Presses `button` during one frame, then releases it.
+    pub fn tap_button(&mut self, button: GamepadButtonType) {
+        self.press_button(button);
+        self.frame();
+        self.release_button(button);
+    }
+
+    fn send_button(&mut self, button: GamepadButtonType, value: f32) {
+        self.app
+            .world
+            .send_event(GamepadEvent::Button(GamepadButtonChangedEvent::new(
+                Self::gamepad(),
+                button,
+                value,
+            )));
+    }
+
+    //////////////////////////////////////// State /////////////////////////////////////////
+
+    /// The run recorded so far, when recording.
+    pub fn recorded_replay(&self) -> Option<Replay> {
+        self.app.world.get_resource::<Recorder>()?.replay.clone()
+    }
+
+    /// The frames played back so far, when playing back.
+    pub fn played_back_frames(&self) -> Option<usize> {
+        Some(self.app.world.get_resource::<Playback>()?.cursor)
+    }
+
+    pub fn set_app_state(&mut self, state: AppState) {
+        self.app
+            .world
+            .resource_mut::<NextState<AppState>>()
+            .set(state);
+    }
+
+    pub fn app_state(&self) -> AppState {
+        self.app.world.resource::<State<AppState>>().get().clone()
+    }
+
+    pub fn has_player(&mut self) -> bool {
+        self.app
+            .world
+            .query_filtered::<(), With<Player>>()
+            .iter(&self.app.world)
+            .next()
+            .is_some()
+    }
+
+    pub fn player_state(&mut self) -> UnitState {
+        *self
+            .app
+            .world
+            .query_filtered::<&UnitState, With<Player>>()
+            .single(&self.app.world)
+    }
+
+    pub fn player_condition(&mut self) -> UnitCondition {
+        *self
+            .app
+            .world
+            .query_filtered::<&UnitCondition, With<Player>>()
+            .single(&self.app.world)
+    }
+
+    pub fn player_translation(&mut self) -> Vec3 {
+        self.app
+            .world
+            .query_filtered::<&Transform, With<Player>>()
+            .single(&self.app.world)
+            .translation
+    }
+
+    /// Teleports the player along the floor.
+    pub fn place_player(&mut self, x: f32) {
+        self.app
+            .world
+            .query_filtered::<&mut Transform, With<Player>>()
+            .single_mut(&mut self.app.world)
+            .translation
+            .x = x;
+    }
+
+    pub fn dash_cooldown_finished(&mut self) -> bool {
+        self.app
+            .world
+            .query_filtered::<&DashCooldown, With<Player>>()
+            .single(&self.app.world)
+            .finished()
+    }
+
+    pub fn life_chunks(&mut self) -> usize {
+        self.app
+            .world
+            .query::<&LifeChunks>()
+            .single(&self.app.world)
+            .len()
+    }
+
+    /// Removes life chunks from the HUD until only `count` are left.
+    pub fn truncate_life_chunks(&mut self, count: usize) {
+        let removed = {
+            let mut chunks = self
+                .app
+                .world
+                .query::<&mut LifeChunks>()
+                .single_mut(&mut self.app.world);
+            let len = chunks.len();
+            chunks.drain(count.min(len)..).collect::<Vec<_>>()
+        };
+        for chunk in removed {
+            self.app.world.despawn(chunk);
+        }
+    }
+
+    /// The current and max ETH of the player.
+    pub fn eth_owned(&mut self) -> (f32, f32) {
+        let eth_owned = self
+            .app
+            .world
+            .query_filtered::<&EthOwned, With<Player>>()
+            .single(&self.app.world);
+        (eth_owned.current, eth_owned.max)
+    }
+
+    pub fn spawn_eth(&mut self, x: f32, quantity: f32) {
+        self.app.world.run_system_once(
+            move |mut commands: Commands, eth_handle: Res<EthHandle>| {
+                spawn_eth(
+                    &mut commands,
+                    Vec3::new(x, -222., 20.),
+                    quantity,
+                    &eth_handle,
+                );
+            },
+        );
+    }
+
+    pub fn eth_tuning(&self) -> EthTuning {
+        self.app.world.resource::<EthTuning>().clone()
+    }
+
+    pub fn ape(&mut self, flank: Flank) -> Option<(f32, ApeLife)> {
+        self.app
+            .world
+            .query_filtered::<(&Transform, &ApeLife, &Flank), With<Ape>>()
+            .iter(&self.app.world)
+            .find(|(_, _, &ape_flank)| ape_flank == flank)
+            .map(|(transform, &life, _)| (transform.translation.x, life))
+    }
+
+    pub fn ape_kind(&mut self, flank: Flank) -> Option<ApeKind> {
+        self.app
+            .world
+            .query_filtered::<(&ApeKind, &Flank), With<Ape>>()
+            .iter(&self.app.world)
+            .find(|(_, &ape_flank)| ape_flank == flank)
+            .map(|(&kind, _)| kind)
+    }
+
+    pub fn ape_traits(&mut self, flank: Flank) -> Option<ApeTraits> {
+        self.app
+            .world
+            .query_filtered::<(&ApeTraits, &Flank), With<Ape>>()
+            .iter(&self.app.world)
+            .find(|(_, &ape_flank)| ape_flank == flank)
+            .map(|(&traits, _)| traits)
+    }
+
+    /// Gives the ape on `flank` new traits, redrawing their layers.
+    pub fn set_ape_traits(&mut self, flank: Flank, traits: ApeTraits) {
+        self.app.world.run_system_once(
+            move |mut commands: Commands,
+                  mut apes_q: Query<(Entity, &mut ApeTraits, &Children, &Flank), With<Ape>>,
+                  layers_q: Query<(), With<TraitLayer>>| {
+                for (ape, mut ape_traits, children, &ape_flank) in apes_q.iter_mut() {
+                    if ape_flank != flank {
+                        continue;
+                    }
+                    for &child in children.iter().filter(|&&child| layers_q.contains(child)) {
+                        commands.entity(child).despawn_recursive();
+                    }
+                    *ape_traits = traits;
+                    spawn_ape_traits_layers(&mut commands, ape, &traits, flank);
+                }
+            },
+        );
+    }
+
+    pub fn tint_ape(&mut self, flank: Flank, color: Color) {
+        let mut apes_q = self
+            .app
+            .world
+            .query_filtered::<(&mut Sprite, &Flank), With<Ape>>();
+        for (mut sprite, &ape_flank) in apes_q.iter_mut(&mut self.app.world) {
+            if ape_flank == flank {
+                sprite.color = color;
+            }
+        }
+    }
+
+    /// The untinted and current color of each trait layer of the ape on `flank`.
+    pub fn ape_traits_layers(&mut self, flank: Flank) -> Vec<(Color, Color)> {
+        let Some(children) = self
+            .app
+            .world
+            .query_filtered::<(&Children, &Flank), With<Ape>>()
+            .iter(&self.app.world)
+            .find(|(_, &ape_flank)| ape_flank == flank)
+            .map(|(children, _)| children.to_vec())
+        else {
+            return vec![];
+        };
+        children
+            .into_iter()
+            .filter_map(|child| {
+                let world = &self.app.world;
+                let layer = world.get::<TraitLayer>(child)?;
+                match (layer.fill, layer.stroke) {
+                    (Some(color), _) => Some((color, world.get::<Fill>(child)?.color)),
+                    (None, Some(color)) => Some((color, world.get::<Stroke>(child)?.color)),
+                    (None, None) => None,
+                }
+            })
+            .collect()
+    }
+
+    pub fn set_ape_life(&mut self, flank: Flank, current: f32) {
+        let mut apes_q = self
+            .app
+            .world
+            .query_filtered::<(&mut ApeLife, &Flank), With<Ape>>();
+        for (mut life, &ape_flank) in apes_q.iter_mut(&mut self.app.world) {
+            if ape_flank == flank {
+                life.current = current;
+            }
+        }
+    }
+
+    pub fn ape_tuning(&self) -> ApeTuning {
+        self.app.world.resource::<ApeTuning>().clone()
+    }
+
+    pub fn set_ape_tuning(&mut self, tune: impl FnOnce(&mut ApeTuning)) {
+        tune(&mut self.app.world.resource_mut::<ApeTuning>());
+    }
+
+    /// Changes the tuning of every kind of ape the same way.
+    pub fn tune_apes(&mut self, tune: impl Fn(&mut ApeKindTuning)) {
+        self.set_ape_tuning(|tuning| {
+            for kind in ApeKind::ALL {
+                tune(tuning.kind_mut(kind));
+            }
+        });
+    }
+
+    /// The quantity of every ETH lying on the floor.
+    pub fn eth_quantities(&mut self) -> Vec<f32> {
+        self.app
+            .world
+            .query::<&Eth>()
+            .iter(&self.app.world)
+            .map(|eth| eth.quantity)
+            .collect()
+    }
+
+    pub fn ape_lives(&mut self) -> Vec<ApeLife> {
+        self.app
+            .world
+            .query_filtered::<&ApeLife, With<Ape>>()
+            .iter(&self.app.world)
+            .copied()
+            .collect()
+    }
+
+    pub fn score(&self) -> usize {
+        self.app.world.resource::<Score>().0
+    }
+
+    pub fn set_score(&mut self, score: usize) {
+        self.app.world.resource_mut::<Score>().0 = score;
+    }
+
+    pub fn wave(&self) -> Wave {
+        self.app.world.resource::<Wave>().clone()
+    }
+
+    pub fn wave_tuning(&self) -> WaveTuning {
+        self.app.world.resource::<WaveTuning>().clone()
+    }
+
+    pub fn set_wave_tuning(&mut self, tune: impl FnOnce(&mut WaveTuning)) {
+        tune(&mut self.app.world.resource_mut::<WaveTuning>());
+    }
+
+    /// Kills the ape on `flank` with a single player attack.
+    pub fn kill_ape(&mut self, flank: Flank) -> bool {
+        self.set_ape_life(flank, 1.);
+        let (ape_x, _) = self.ape(flank).unwrap();
+        self.place_player(ape_x);
+        self.tap(PlayerInput::ATTACK);
+        self.run_until(120, |game| game.ape(flank).is_none())
+    }
+
+    pub fn boss(&mut self) -> Option<(f32, ApeLife)> {
+        self.app
+            .world
+            .query_filtered::<(&Transform, &ApeLife), With<Boss>>()
+            .iter(&self.app.world)
+            .next()
+            .map(|(transform, &life)| (transform.translation.x, life))
+    }
+
+    pub fn set_boss_life(&mut self, current: f32) {
+        let mut boss_q = self.app.world.query_filtered::<&mut ApeLife, With<Boss>>();
+        for mut life in boss_q.iter_mut(&mut self.app.world) {
+            life.current = current;
+        }
+    }
+
+    pub fn boss_tuning(&self) -> BossTuning {
+        self.app.world.resource::<BossTuning>().clone()
+    }
+
+    pub fn set_wave(&mut self, number: usize) {
+        self.app.world.resource_mut::<Wave>().number = number;
+    }
+
+    /// The apes currently blinking their eyes before firing.
+    pub fn charging_apes(&mut self) -> Vec<Entity> {
+        self.app
+            .world
+            .query::<(&ApeAttackOwner, &StagedAnimation)>()
+            .iter(&self.app.world)
+            .filter(|(_, anim)| matches!(anim, StagedAnimation::Init { .. }))
+            .map(|(owner, _)| **owner)
+            .collect()
+    }
+
+    /// Pushes every ape scheduled attack far away, for tests starting attacks themselves.
+    pub fn hold_ape_attacks(&mut self) {
+        let mut schedules_q = self.app.world.query::<&mut ApeAttackSchedule>();
+        for mut schedule in schedules_q.iter_mut(&mut self.app.world) {
+            schedule.set_duration(Duration::from_secs(1000));
+            schedule.reset();
+        }
+    }
+
+    /// Makes the ape on `flank` start `attack` toward the player.
+    pub fn start_ape_attack(&mut self, flank: Flank, attack: ApeAttackKind) {
+        self.app.world.run_system_once(
+            move |mut commands: Commands,
+                  tuning: Res<ApeTuning>,
+                  player_q: Query<&Transform, With<Player>>,
+                  apes_q: Query<(Entity, &Transform, &ApeAttackSpec, &Flank), With<Ape>>| {
+                let player_x = player_q.single().translation.x;
+                for (ape, transform, attack_spec, &ape_flank) in apes_q.iter() {
+                    if ape_flank == flank {
+                        spawn_ape_attack(
+                            &mut commands,
+                            ape,
+                            attack,
+                            attack_spec,
+                            transform.translation.x,
+                            player_x,
+                            &tuning.attacks,
+                        );
+                    }
+                }
+            },
+        );
+    }
+
+    /// The position of every projectile and whether the player hit it back.
+    pub fn projectiles(&mut self) -> Vec<(Vec2, bool)> {
+        self.app
+            .world
+            .query::<(&Transform, &Projectile)>()
+            .iter(&self.app.world)
+            .map(|(transform, projectile)| {
+                (
+                    transform.translation.truncate(),
+                    projectile.deflected.is_some(),
+                )
+            })
+            .collect()
+    }
+
+    pub fn shards_count(&mut self) -> usize {
+        self.app
+            .world
+            .query::<&Shard>()
+            .iter(&self.app.world)
+            .count()
+    }
+
+    /// Makes the ape on `flank` throw a banana from `start`.
+    pub fn throw_projectile(&mut self, flank: Flank, start: Vec2, velocity: Vec2) {
+        self.app.world.run_system_once(
+            move |mut commands: Commands, apes_q: Query<(Entity, &Flank), With<Ape>>| {
+                for (ape, &ape_flank) in apes_q.iter() {
+                    if ape_flank == flank {
+                        spawn_projectile(
+                            &mut commands,
+                            ape,
+                            ProjectileKind::Banana,
+                            start,
+                            velocity,
+                        );
+                    }
+                }
+            },
+        );
+    }
+
+    /// Lifts the ape on `flank` onto a ledge at `height`, as if spawned on a perched slot.
+    pub fn perch_ape(&mut self, flank: Flank, height: f32) {
+        let mut apes_q = self
+            .app
+            .world
+            .query_filtered::<(&mut Transform, &mut ApeSlot, &Flank), With<Ape>>();
+        for (mut transform, mut slot, &ape_flank) in apes_q.iter_mut(&mut self.app.world) {
+            if ape_flank == flank {
+                transform.translation.y = height;
+                slot.perch = Some(height);
+            }
+        }
+    }
+
+    /// The position of the ape holding each spawn slot, by slot index.
+    pub fn apes_by_slot(&mut self) -> HashMap<usize, Vec2> {
+        self.app
+            .world
+            .query_filtered::<(&Transform, &ApeSlot), With<Ape>>()
+            .iter(&self.app.world)
+            .map(|(transform, slot)| (slot.index, transform.translation.truncate()))
+            .collect()
+    }
+
+    /// The position of every dying ape.
+    pub fn dying_apes(&mut self) -> Vec<Vec2> {
+        self.app
+            .world
+            .query_filtered::<&Transform, With<ApeDeath>>()
+            .iter(&self.app.world)
+            .map(|transform| transform.translation.truncate())
+            .collect()
+    }
+
+    pub fn power_ups(&mut self) -> Vec<PowerUp> {
+        self.app
+            .world
+            .query::<&PowerUp>()
+            .iter(&self.app.world)
+            .copied()
+            .collect()
+    }
+
+    /// The shares of the life shown by the health bar of the ape on `flank`, as left and
+    /// trailing behind.
+    pub fn ape_health_bar(&mut self, flank: Flank) -> Option<(f32, f32)> {
+        let health_bar = self
+            .app
+            .world
+            .query_filtered::<(&ApeHealthBar, &Flank), With<Ape>>()
+            .iter(&self.app.world)
+            .find(|(_, &ape_flank)| ape_flank == flank)
+            .map(|(&health_bar, _)| health_bar)?;
+        let scale_x = |entity| self.app.world.get::<Transform>(entity).unwrap().scale.x;
+        Some((scale_x(health_bar.fill), scale_x(health_bar.trail)))
+    }
+
+    pub fn ape_nameplate(&mut self, flank: Flank) -> Option<String> {
+        let root = self
+            .app
+            .world
+            .query_filtered::<(&ApeHealthBar, &Flank), With<Ape>>()
+            .iter(&self.app.world)
+            .find(|(_, &ape_flank)| ape_flank == flank)
+            .map(|(health_bar, _)| health_bar.root)?;
+        self.app
+            .world
+            .get::<Children>(root)?
+            .iter()
+            .find_map(|&child| self.app.world.get::<Text>(child))
+            .map(|text| text.sections[0].value.clone())
+    }
+
+    /// The lines of the kill feed, newest first.
+    pub fn kill_feed(&mut self) -> Vec<String> {
+        let Some(entries) = self
+            .app
+            .world
+            .query_filtered::<&Children, With<KillFeed>>()
+            .iter(&self.app.world)
+            .next()
+        else {
+            return vec![];
+        };
+        entries
+            .iter()
+            .rev()
+            .filter_map(|&entry| self.app.world.get::<Text>(entry))
+            .map(|text| text.sections[0].value.clone())
+            .collect()
+    }
+
+    pub fn spawn_minion(&mut self, x: f32) {
+        self.app.world.run_system_once(
+            move |mut commands: Commands, asset_server: Res<AssetServer>| {
+                spawn_minion(&mut commands, &asset_server, ApeKind::King, x);
+            },
+        );
+    }
+
+    /// The position of every minion.
+    pub fn minions(&mut self) -> Vec<f32> {
+        self.app
+            .world
+            .query_filtered::<&Transform, With<Minion>>()
+            .iter(&self.app.world)
+            .map(|transform| transform.translation.x)
+            .collect()
+    }
+
+    /// Whether the ape on `flank`, or any ape, is still entering the arena.
+    pub fn ape_entering(&mut self, flank: Option<Flank>) -> bool {
+        self.app
+            .world
+            .query_filtered::<&Flank, (With<Ape>, With<ApeEntering>)>()
+            .iter(&self.app.world)
+            .any(|&ape_flank| flank.is_none_or(|flank| flank == ape_flank))
+    }
+
+    pub fn ape_enraged(&mut self, flank: Flank) -> bool {
+        self.app
+            .world
+            .query_filtered::<(&ApeEnrage, &Flank), With<Ape>>()
+            .iter(&self.app.world)
+            .any(|(enrage, &ape_flank)| enrage.enraged && ape_flank == flank)
+    }
+
+    pub fn ape_chaining(&mut self, flank: Flank) -> bool {
+        self.app
+            .world
+            .query_filtered::<&Flank, (With<Ape>, With<ApeChain>)>()
+            .iter(&self.app.world)
+            .any(|&ape_flank| ape_flank == flank)
+    }
+
+    /// The time the ape on `flank` charges its lasers before firing.
+    pub fn ape_laser_charge(&mut self, flank: Flank) -> Option<Duration> {
+        self.app
+            .world
+            .query_filtered::<(&ApeAttackSpec, &Flank), With<Ape>>()
+            .iter(&self.app.world)
+            .find(|(_, &ape_flank)| ape_flank == flank)
+            .map(|(attack_spec, _)| attack_spec.init_duration.duration())
+    }
+
+    /// Whether the ape on `flank` owns an attack, whatever its stage.
+    pub fn ape_attacking(&mut self, flank: Flank) -> bool {
+        let owners = self
+            .app
+            .world
+            .query::<&ApeAttackOwner>()
+            .iter(&self.app.world)
+            .map(|owner| **owner)
+            .collect::<Vec<_>>();
+        self.app
+            .world
+            .query_filtered::<(Entity, &Flank), With<Ape>>()
+            .iter(&self.app.world)
+            .any(|(ape, &ape_flank)| ape_flank == flank && owners.contains(&ape))
+    }
+
+    pub fn ape_stunned(&mut self, flank: Flank) -> bool {
+        self.app
+            .world
+            .query_filtered::<&Flank, (With<Ape>, With<ApeStunned>)>()
+            .iter(&self.app.world)
+            .any(|&ape_flank| ape_flank == flank)
+    }
+}
@@ -0,0 +1,44 @@
+mod common;
+
+use angry_apes::prelude::*;
+use common::TestGame;
+
+/// Kills the ape on `flank` and waits for the next one to show up.
+fn respawn_ape(game: &mut TestGame, flank: Flank) {
+    assert!(game.kill_ape(flank), "ape was not killed");
+    let respawned = game.run_until(600, |game| game.ape(flank).is_some());
+    assert!(respawned, "ape was not respawned");
+}
+
+#[test]
+fn respawned_ape_enters_before_fighting() {
+    let mut game = TestGame::new();
+    let tuning = game.ape_tuning();
+    let start = tuning.slots[0].start;
+
+    respawn_ape(&mut game, Flank::Left);
+    assert!(game.ape_entering(Some(Flank::Left)));
+
+    let entered = game.run_until(120, |game| !game.ape_entering(Some(Flank::Left)));
+    assert!(entered, "ape never finished its entrance");
+    let (ape_x, _) = game.ape(Flank::Left).unwrap();
+    assert_eq!(ape_x, start);
+}
+
+#[test]
+fn entering_ape_can_neither_be_hurt_nor_attack() {
+    let mut game = TestGame::new();
+    respawn_ape(&mut game, Flank::Left);
+    let (_, life) = game.ape(Flank::Left).unwrap();
+
+    while game.ape_entering(Some(Flank::Left)) {
+        let (ape_x, _) = game.ape(Flank::Left).unwrap();
+        game.place_player(ape_x);
+        if game.player_state() == UnitState::Stand {
+            game.tap(PlayerInput::ATTACK);
+        }
+        game.frame();
+        assert!(!game.ape_attacking(Flank::Left));
+    }
+    assert_eq!(game.ape(Flank::Left).unwrap().1.current, life.current);
+}
@@ -0,0 +1,47 @@
+mod common;
+
+use angry_apes::prelude::*;
+use common::TestGame;
+
+#[test]
+fn eth_pickup_upgrades_player() {
+    let mut game = TestGame::new();
+    let tuning = game.eth_tuning();
+    assert_eq!(game.eth_owned(), (0., tuning.max));
+    assert_eq!(game.player_condition(), UnitCondition::Normal);
+
+    let player_x = game.player_translation().x;
+    let mut picked = 0.;
+    while picked + tuning.quantity < tuning.max {
+        game.spawn_eth(player_x, tuning.quantity);
+        game.frame();
+        picked += tuning.quantity;
+        assert_eq!(game.eth_owned().0, picked);
+        assert_eq!(game.player_condition(), UnitCondition::Normal);
+    }
+
+    game.spawn_eth(player_x, tuning.quantity);
+    game.frames(2);
+    assert_eq!(game.player_condition(), UnitCondition::Upgraded);
+    // Upgraded ETH starts decaying right away
+    assert!(game.eth_owned().0 > picked);
+}
+
+#[test]
+fn upgraded_player_is_immune_to_lasers() {
+    let mut game = TestGame::new();
+    let tuning = game.eth_tuning();
+
+    let player_x = game.player_translation().x;
+    game.spawn_eth(player_x, tuning.max);
+    game.frames(2);
+    assert_eq!(game.player_condition(), UnitCondition::Upgraded);
+
+    let chunks = game.life_chunks();
+    game.run_until(300, |game| {
+        let (ape_x, _) = game.ape(Flank::Left).unwrap();
+        game.place_player(ape_x + 312.);
+        game.player_condition() == UnitCondition::Normal
+    });
+    assert_eq!(game.life_chunks(), chunks);
+}
@@ -0,0 +1,71 @@
+use angry_apes::prelude::*;
+
+fn high_score(kills: usize, survival_secs: f32) -> HighScore {
+    HighScore {
+        kills,
+        survival_secs,
+        eth_collected: 0.,
+        date: "2024-01-01".to_string(),
+    }
+}
+
+fn full_table() -> HighScores {
+    let mut high_scores = HighScores::default();
+    for kills in [50, 40, 30, 20, 10] {
+        high_scores.insert(high_score(kills, 60.));
+    }
+    high_scores
+}
+
+fn kills(high_scores: &HighScores) -> Vec<usize> {
+    high_scores
+        .entries
+        .iter()
+        .map(|entry| entry.kills)
+        .collect()
+}
+
+#[test]
+fn run_ranks_into_a_full_table() {
+    let mut high_scores = full_table();
+    assert_eq!(high_scores.entries.len(), HighScores::MAX_ENTRIES);
+
+    assert_eq!(high_scores.insert(high_score(35, 60.)), Some(2));
+    assert_eq!(kills(&high_scores), [50, 40, 35, 30, 20]);
+}
+
+#[test]
+fn run_below_a_full_table_does_not_rank() {
+    let mut high_scores = full_table();
+
+    assert_eq!(high_scores.insert(high_score(5, 600.)), None);
+    // Ties do not push older runs out
+    assert_eq!(high_scores.insert(high_score(10, 60.)), None);
+    assert_eq!(kills(&high_scores), [50, 40, 30, 20, 10]);
+}
+
+#[test]
+fn longest_survival_breaks_kills_ties() {
+    let mut high_scores = full_table();
+
+    assert_eq!(high_scores.insert(high_score(30, 90.)), Some(2));
+    assert_eq!(high_scores.insert(high_score(30, 30.)), Some(4));
+    let survivals = high_scores
+        .entries
+        .iter()
+        .map(|entry| (entry.kills, entry.survival_secs))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        survivals,
+        [(50, 60.), (40, 60.), (30, 90.), (30, 60.), (30, 30.)]
+    );
+}
+
+#[test]
+fn civil_date_of_epoch_millis() {
+    assert_eq!(civil_date(0), "1970-01-01");
+    // 2024-02-29T23:59:59.999Z, a leap day
+    assert_eq!(civil_date(1_709_251_199_999), "2024-02-29");
+    assert_eq!(civil_date(1_709_251_200_000), "2024-03-01");
+    assert_eq!(civil_date(-1), "1969-12-31");
+}
@@ -0,0 +1,65 @@
+mod common;
+
+use angry_apes::prelude::*;
+use common::TestGame;
+
+#[test]
+fn killed_ape_collapses_before_vanishing() {
+    let mut game = TestGame::new();
+
+    assert!(game.kill_ape(Flank::Left), "ape was not killed");
+    game.frame();
+    assert_eq!(game.dying_apes().len(), 1);
+
+    game.seconds(ApeDeath::DURATION / 2.);
+    let position = game.dying_apes()[0];
+    assert!(position.y < 0., "ape did not collapse");
+
+    game.seconds(ApeDeath::DURATION / 2. + 0.1);
+    assert!(game.dying_apes().is_empty());
+}
+
+#[test]
+fn loot_is_picked_up_once_on_the_floor() {
+    let mut game = TestGame::new();
+    game.tune_apes(|ape| {
+        ape.loot = vec![LootEntry {
+            drop: LootDrop::Eth(7.),
+            chance: 1.,
+        }]
+    });
+
+    assert!(game.kill_ape(Flank::Left), "ape was not killed");
+    game.frame();
+    // Right where the loot falls
+    let position = game.dying_apes()[0];
+    game.place_player(position.x);
+    assert!(game.eth_quantities().contains(&7.));
+    assert_eq!(game.eth_owned().0, 0.);
+
+    let picked = game.run_until(120, |game| game.eth_owned().0 == 7.);
+    assert!(picked, "loot was never picked");
+}
+
+#[test]
+fn heart_gives_back_a_life_chunk() {
+    let mut game = TestGame::new();
+    let chunks = game.life_chunks();
+    game.truncate_life_chunks(chunks - 1);
+    game.tune_apes(|ape| {
+        ape.loot = vec![LootEntry {
+            drop: LootDrop::PowerUp(PowerUp::Heart),
+            chance: 1.,
+        }]
+    });
+
+    assert!(game.kill_ape(Flank::Left), "ape was not killed");
+    game.frame();
+    assert_eq!(game.power_ups(), vec![PowerUp::Heart]);
+    let position = game.dying_apes()[0];
+    game.place_player(position.x);
+
+    let healed = game.run_until(120, |game| game.life_chunks() == chunks);
+    assert!(healed, "heart was never picked");
+    assert!(game.power_ups().is_empty());
+}
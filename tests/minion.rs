@@ -0,0 +1,59 @@
+mod common;
+
+use angry_apes::prelude::*;
+use common::TestGame;
+
+#[test]
+fn apes_send_minions_up_to_a_limit() {
+    let mut game = TestGame::new();
+    game.set_ape_tuning(|tuning| {
+        tuning.minions.interval = 0.5;
+        tuning.minions.max = 2;
+    });
+    assert!(game.minions().is_empty());
+
+    let sent = game.run_until(120, |game| !game.minions().is_empty());
+    assert!(sent, "no minion was sent");
+    for _ in 0..120 {
+        game.frame();
+        assert!(game.minions().len() <= 2);
+    }
+}
+
+#[test]
+fn minion_rushes_and_wounds_the_player() {
+    let mut game = TestGame::new();
+    let chunks = game.life_chunks();
+    game.place_player(0.);
+    game.spawn_minion(300.);
+    game.frame();
+
+    game.seconds(0.5);
+    assert!(game.minions()[0] < 300.);
+
+    let hit = game.run_until(120, |game| game.life_chunks() < chunks);
+    assert!(hit, "minion never reached the player");
+    assert_eq!(game.life_chunks(), chunks - 1);
+    assert!(game.minions().is_empty());
+}
+
+#[test]
+fn minion_dies_in_one_hit_and_drops_eth() {
+    let mut game = TestGame::new();
+    let tuning = game.ape_tuning();
+    let chunks = game.life_chunks();
+    game.set_ape_tuning(|tuning| tuning.minions.speed = 0.001);
+    game.place_player(0.);
+    game.spawn_minion(60.);
+    game.frame();
+
+    game.tap(PlayerInput::ATTACK);
+    let killed = game.run_until(120, |game| game.minions().is_empty());
+    assert!(killed, "minion was not killed");
+    assert_eq!(game.life_chunks(), chunks);
+
+    let dropped = game.run_until(120, |game| {
+        game.eth_quantities().contains(&tuning.minions.eth)
+    });
+    assert!(dropped, "minion dropped no ETH");
+}
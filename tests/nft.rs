@@ -0,0 +1,101 @@
+mod common;
+
+use angry_apes::prelude::*;
+use common::TestGame;
+
+#[test]
+fn ape_stats_are_derived_from_its_traits() {
+    let mut game = TestGame::new();
+    let tuning = game.ape_tuning();
+    let kind = game.ape_kind(Flank::Left).unwrap();
+    let traits = game.ape_traits(Flank::Left).unwrap();
+
+    let (_, life) = game.ape(Flank::Left).unwrap();
+    assert_eq!(life.max, tuning.kind(kind).life * traits.life_scale());
+
+    let charge = game.ape_laser_charge(Flank::Left).unwrap().as_secs_f32();
+    let expected = tuning.kind(kind).laser_charge * traits.laser_charge_scale();
+    assert!((charge - expected).abs() < 1e-3, "{charge} != {expected}");
+}
+
+#[test]
+fn rarest_trait_gives_the_ape_rarity() {
+    let traits = ApeTraits {
+        token_id: 1234,
+        fur: Fur::Brown,
+        hat: Hat::Cap,
+        eyes: Eyes::Bored,
+        accessory: Accessory::Chain,
+    };
+    assert_eq!(traits.rarity(), Rarity::Epic);
+    assert_eq!(traits.title(ApeKind::King), "#1234 Epic King");
+}
+
+#[test]
+fn missing_rarities_fall_back_to_lower_ones() {
+    let mut rng = GameRng::from_seed(TestGame::SEED);
+    let tuning = ApeTraitsTuning {
+        rare: 0.,
+        epic: 0.,
+        legendary: 1.,
+    };
+
+    let traits = ApeTraits::roll(&mut rng, &tuning);
+    assert_eq!(traits.fur, Fur::Golden);
+    assert_eq!(traits.eyes, Eyes::Laser);
+    // No legendary accessory, the epic one is the rarest
+    assert_eq!(traits.accessory, Accessory::Chain);
+}
+
+#[test]
+fn token_id_and_rarity_show_on_spawn_and_in_kill_feed() {
+    let mut game = TestGame::new();
+    let kind = game.ape_kind(Flank::Left).unwrap();
+    let traits = game.ape_traits(Flank::Left).unwrap();
+
+    let nameplate = game.ape_nameplate(Flank::Left).unwrap();
+    assert!(nameplate.starts_with(&traits.title(kind)), "{nameplate}");
+    assert!(game.kill_feed().is_empty());
+
+    assert!(game.kill_ape(Flank::Left), "ape was not killed");
+    let feed = game.run_until(120, |game| !game.kill_feed().is_empty());
+    assert!(feed, "kill was not reported");
+    assert!(game.kill_feed()[0].starts_with(&traits.title(kind)));
+}
+
+#[test]
+fn traits_layers_follow_the_ape_tint_but_its_fur() {
+    let mut game = TestGame::new();
+    let traits = ApeTraits {
+        token_id: 1,
+        fur: Fur::Tan,
+        hat: Hat::Cap,
+        eyes: Eyes::Laser,
+        accessory: Accessory::Chain,
+    };
+    game.set_ape_traits(Flank::Left, traits);
+    game.tint_ape(Flank::Left, Fur::Tan.color());
+    game.frame();
+    let layers = game.ape_traits_layers(Flank::Left);
+    assert_eq!(layers.len(), 3);
+    assert!(layers.iter().all(|(base, color)| base == color));
+
+    game.tint_ape(Flank::Left, ApeStunned::TINT.with_a(0.5));
+    game.frame();
+    let tint = Vec4::from(ApeStunned::TINT.with_a(0.5).as_rgba_f32());
+    for (base, color) in game.ape_traits_layers(Flank::Left) {
+        assert_eq!(color, base * tint);
+    }
+}
+
+#[test]
+fn kill_feed_is_cleared_with_the_game() {
+    let mut game = TestGame::new();
+    assert!(game.kill_ape(Flank::Left), "ape was not killed");
+    let feed = game.run_until(120, |game| !game.kill_feed().is_empty());
+    assert!(feed, "kill was not reported");
+
+    game.set_app_state(AppState::GameOver);
+    game.frames(2);
+    assert!(game.kill_feed().is_empty());
+}
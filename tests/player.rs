@@ -0,0 +1,70 @@
+mod common;
+
+use angry_apes::prelude::*;
+use common::TestGame;
+
+const FLOOR: f32 = -170.;
+
+#[test]
+fn jump_lands_back_on_the_floor() {
+    let mut game = TestGame::new();
+    assert_eq!(game.player_state(), UnitState::Stand);
+
+    game.tap(PlayerInput::JUMP);
+    game.frame();
+    assert_eq!(game.player_state(), UnitState::Jump);
+
+    game.seconds(0.2);
+    assert!(game.player_translation().y > FLOOR);
+
+    let landed = game.run_until(180, |game| game.player_state() == UnitState::Stand);
+    assert!(landed, "player never landed");
+    assert_eq!(game.player_translation().y, FLOOR);
+}
+
+#[test]
+fn dash_falls_back_then_cools_down() {
+    let mut game = TestGame::new();
+    game.seconds(0.5);
+
+    game.tap(PlayerInput::DASH);
+    game.frame();
+    assert_eq!(game.player_state(), UnitState::Dash);
+
+    let stopped = game.run_until(60, |game| game.player_state() == UnitState::Stand);
+    assert!(stopped, "dash never ended");
+    assert!(!game.dash_cooldown_finished());
+
+    // Still cooling down
+    game.tap(PlayerInput::DASH);
+    game.frame();
+    assert_eq!(game.player_state(), UnitState::Stand);
+
+    let cooled = game.run_until(60, |game| game.dash_cooldown_finished());
+    assert!(cooled, "dash never cooled down");
+
+    game.tap(PlayerInput::DASH);
+    game.frame();
+    assert_eq!(game.player_state(), UnitState::Dash);
+}
+
+#[test]
+fn gamepad_dash_cools_down() {
+    let mut game = TestGame::new();
+    game.connect_gamepad();
+    game.seconds(0.5);
+
+    game.tap_button(GamepadButtonType::East);
+    game.frame();
+    assert_eq!(game.player_state(), UnitState::Dash);
+
+    game.run_until(60, |game| game.player_state() == UnitState::Stand);
+    game.tap_button(GamepadButtonType::East);
+    game.frame();
+    assert_ne!(game.player_state(), UnitState::Dash);
+
+    game.run_until(60, |game| game.dash_cooldown_finished());
+    game.tap_button(GamepadButtonType::East);
+    game.frame();
+    assert_eq!(game.player_state(), UnitState::Dash);
+}
@@ -0,0 +1,99 @@
+mod common;
+
+use angry_apes::prelude::*;
+use common::TestGame;
+
+fn sample_replay() -> Replay {
+    let actions = PlayerActions {
+        orientation: Some(Orientation::Left),
+        jump: true,
+        attack: true,
+        direction_pressed: true,
+        ..default()
+    };
+    Replay {
+        seed: 0xdead_beef_cafe,
+        frames: vec![
+            ReplayFrame {
+                delta: TestGame::FRAME,
+                actions: PlayerActions::default(),
+            },
+            ReplayFrame {
+                delta: Duration::from_micros(33_334),
+                actions: PlayerActions::from_bits(actions.to_bits()),
+            },
+        ],
+    }
+}
+
+#[test]
+fn replay_survives_a_bytes_round_trip() {
+    let replay = sample_replay();
+    let bytes = replay.to_bytes();
+    assert_eq!(Replay::from_bytes(&bytes).unwrap(), replay);
+}
+
+#[test]
+fn invalid_replays_are_rejected() {
+    let bytes = sample_replay().to_bytes();
+
+    let mut not_a_replay = bytes.clone();
+    not_a_replay[..4].copy_from_slice(b"NOPE");
+    assert!(matches!(
+        Replay::from_bytes(&not_a_replay),
+        Err(ReplayError::NotAReplay)
+    ));
+    assert!(matches!(
+        Replay::from_bytes(&bytes[..8]),
+        Err(ReplayError::NotAReplay)
+    ));
+
+    let mut future = bytes.clone();
+    future[4] = 2;
+    assert!(matches!(
+        Replay::from_bytes(&future),
+        Err(ReplayError::UnsupportedVersion(2))
+    ));
+
+    assert!(matches!(
+        Replay::from_bytes(&bytes[..bytes.len() - 1]),
+        Err(ReplayError::Truncated)
+    ));
+}
+
+#[test]
+fn recorded_run_plays_back_identically() {
+    let path = std::env::temp_dir().join(format!("angry-apes-{}.replay", std::process::id()));
+    let mut recording = TestGame::with_replay(ReplayPlugin::Record(path.clone()));
+    recording.press(PlayerInput::RIGHT);
+    recording.frames(20);
+    recording.release(PlayerInput::RIGHT);
+    recording.tap(PlayerInput::ATTACK);
+    recording.frames(30);
+    recording.tap(PlayerInput::JUMP);
+    recording.frames(30);
+    recording.tap(PlayerInput::LEFT);
+    recording.tap(PlayerInput::DASH);
+    recording.seconds(5.);
+
+    let replay = recording.recorded_replay().unwrap();
+    assert_eq!(replay.seed, TestGame::SEED);
+    assert!(replay.frames.iter().any(|frame| frame.actions.attack));
+
+    // Only saved once the run is over
+    std::fs::write(&path, replay.to_bytes()).unwrap();
+    let mut playback = TestGame::with_replay(ReplayPlugin::Playback(path.clone()));
+    std::fs::remove_file(&path).unwrap();
+
+    let frames = replay.frames.len();
+    let done = playback.run_until(frames, |game| game.played_back_frames() == Some(frames));
+    assert!(done, "playback did not reach the end of the replay");
+
+    assert_eq!(playback.player_state(), recording.player_state());
+    assert_eq!(playback.life_chunks(), recording.life_chunks());
+    assert_eq!(playback.score(), recording.score());
+    assert_eq!(
+        playback.player_translation(),
+        recording.player_translation()
+    );
+}
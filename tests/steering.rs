@@ -0,0 +1,63 @@
+mod common;
+
+use angry_apes::prelude::*;
+use common::TestGame;
+
+/// A game whose apes only move, steered by `steering`.
+fn steered_game(steering: ApeSteering) -> TestGame {
+    let mut game = TestGame::new();
+    game.hold_ape_attacks();
+    game.tune_apes(|ape| ape.steering = steering);
+    game
+}
+
+#[test]
+fn chasing_ape_keeps_the_player_in_lasers_range() {
+    let mut game = steered_game(ApeSteering::Chase);
+    game.place_player(0.);
+
+    game.seconds(6.);
+    let (ape_x, _) = game.ape(Flank::Left).unwrap();
+    // Eyes lasers of a regular reach, from a regular ape
+    let range = ApeAttackRange::lasers(1.0);
+    let kind = game.ape_kind(Flank::Left).unwrap();
+    let reach = game.ape_tuning().kind(kind).laser_reach;
+    let optimal = ApeAttackSpec::LASERS_X * 0.8 + range.scaled_by(reach).center();
+    assert!(
+        (ape_x + optimal).abs() < 5.,
+        "ape at {ape_x}, expected around {}",
+        -optimal
+    );
+}
+
+#[test]
+fn chasing_ape_backs_off_from_the_player() {
+    let mut game = steered_game(ApeSteering::Chase);
+    let retreat_distance = game.ape_tuning().steering.retreat_distance;
+    // Away from its flank bounds first
+    game.place_player(0.);
+    game.seconds(6.);
+    let (ape_x, _) = game.ape(Flank::Left).unwrap();
+    game.place_player(ape_x + 60.);
+
+    game.seconds(2.);
+    let (ape_x, _) = game.ape(Flank::Left).unwrap();
+    let distance = (game.player_translation().x - ape_x).abs();
+    assert!(
+        distance >= retreat_distance - 1.,
+        "ape stayed {distance} away"
+    );
+}
+
+#[test]
+fn patrolling_ape_stays_on_its_flank() {
+    let mut game = steered_game(ApeSteering::Patrol);
+    let slot = game.ape_tuning().slots[0];
+    game.place_player(0.);
+
+    for _ in 0..20 {
+        game.seconds(0.5);
+        let (ape_x, _) = game.ape(Flank::Left).unwrap();
+        assert!(slot.min <= ape_x && ape_x <= slot.max);
+    }
+}
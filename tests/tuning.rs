@@ -0,0 +1,64 @@
+use angry_apes::prelude::*;
+
+const TUNING: &str = include_str!("../assets/tuning.ron");
+
+/// Loads the shipped tuning file with `from` replaced by `to`.
+fn load_edited(from: &str, to: &str) -> Result<Tuning, TuningError> {
+    assert!(TUNING.contains(from), "{from} is not in the tuning file");
+    Tuning::from_ron(TUNING.replacen(from, to, 1).as_bytes())
+}
+
+fn error_message(from: &str, to: &str) -> String {
+    load_edited(from, to)
+        .expect_err("tuning should be rejected")
+        .to_string()
+}
+
+#[test]
+fn shipped_tuning_is_valid() {
+    assert!(Tuning::from_ron(TUNING.as_bytes()).is_ok());
+}
+
+#[test]
+fn unknown_field_is_named() {
+    let message = error_message(
+        "move_velocity: 150.0,",
+        "move_velocity: 150.0, run_velocity: 300.0,",
+    );
+    assert!(message.starts_with("malformed tuning file"), "{message}");
+    assert!(message.contains("run_velocity"), "{message}");
+}
+
+#[test]
+fn negative_velocity_is_named() {
+    let message = error_message("move_velocity: 150.0,", "move_velocity: -150.0,");
+    assert_eq!(
+        message,
+        "invalid `player.move_velocity`: must be strictly positive, got -150"
+    );
+}
+
+#[test]
+fn out_of_range_odds_are_named() {
+    let message = error_message("chain_chance: 0.4,", "chain_chance: 1.5,");
+    assert_eq!(
+        message,
+        "invalid `ape.enrage.chain_chance`: must be within [0, 1], got 1.5"
+    );
+
+    let message = error_message("rare: 0.25,", "rare: 0.95,");
+    assert_eq!(
+        message,
+        "invalid `ape.traits`: odds must add up to 1 at most, got 1.05"
+    );
+}
+
+#[test]
+fn waves_may_not_grow() {
+    assert!(load_edited("life_growth: 0.15,", "life_growth: 0.0,").is_ok());
+    let message = error_message("life_growth: 0.15,", "life_growth: -0.1,");
+    assert_eq!(
+        message,
+        "invalid `wave.life_growth`: must not be negative, got -0.1"
+    );
+}
@@ -0,0 +1,66 @@
+mod common;
+
+use angry_apes::prelude::*;
+use common::TestGame;
+
+#[test]
+fn first_wave_has_an_ape_on_each_flank() {
+    let mut game = TestGame::new();
+    assert_eq!(game.wave().number, 1);
+    assert!(game.ape(Flank::Left).is_some());
+    assert!(game.ape(Flank::Right).is_some());
+    assert_eq!(game.ape_lives().len(), 2);
+
+    // Killed apes come back as long as the wave lasts
+    let tuning = game.ape_tuning();
+    assert!(game.kill_ape(Flank::Left), "ape was not killed");
+    game.seconds(tuning.respawn_delay + 0.1);
+    assert!(game.ape(Flank::Left).is_some());
+}
+
+#[test]
+fn cleared_wave_brings_tougher_apes_after_a_breather() {
+    let mut game = TestGame::new();
+    let wave_tuning = game.wave_tuning();
+    game.set_score(wave_tuning.kills - 1);
+
+    assert!(game.kill_ape(Flank::Left), "ape was not killed");
+    assert!(matches!(game.wave().phase, WavePhase::Clearing));
+    assert!(game.kill_ape(Flank::Right), "ape was not killed");
+    game.frame();
+    assert!(matches!(game.wave().phase, WavePhase::Breather(_)));
+
+    game.seconds(wave_tuning.breather - 0.5);
+    assert!(game.ape_lives().is_empty(), "apes during the breather");
+
+    let started = game.run_until(120, |game| game.ape(Flank::Left).is_some());
+    assert!(started, "next wave never started");
+    let wave = game.wave();
+    assert_eq!(wave.number, 2);
+    assert_eq!(wave.kills_at_start, wave_tuning.kills + 1);
+
+    let kind = game.ape_kind(Flank::Left).unwrap();
+    let traits = game.ape_traits(Flank::Left).unwrap();
+    let (_, life) = game.ape(Flank::Left).unwrap();
+    let base_life = game.ape_tuning().kind(kind).life * traits.life_scale();
+    assert_eq!(life.max, base_life * (1. + wave_tuning.life_growth));
+}
+
+#[test]
+fn later_waves_hold_every_spawn_slot() {
+    let mut game = TestGame::new();
+    let tuning = game.ape_tuning();
+    let wave_tuning = game.wave_tuning();
+    let needed = 1 + (tuning.slots.len() - wave_tuning.first_apes) * wave_tuning.apes_every;
+    game.set_wave(needed);
+
+    // The new ape only stands on its slot once entered
+    game.seconds(tuning.respawn_delay + tuning.entrance_duration + 0.1);
+    let apes = game.apes_by_slot();
+    assert_eq!(apes.len(), tuning.slots.len());
+    for (index, slot) in tuning.slots.iter().enumerate() {
+        let position = apes[&index];
+        assert_eq!(position.y, slot.perch.unwrap_or(0.));
+        assert!(slot.min <= position.x && position.x <= slot.max);
+    }
+}